@@ -131,12 +131,17 @@ pub struct Cli {
     #[arg(long)]
     pub http_no_retry: bool,
 
-    /// HTTP server host
-    /// Defaults to 127.0.0.1 when --no-tls is used (local testing)
+    /// HTTP server host (hostname, IPv4, or IPv6 address without a zone ID)
+    /// Defaults to 127.0.0.1 (or ::1 with --ipv6) when --no-tls is used (local testing)
     /// Defaults to mcp.kodegen.ai otherwise (production)
-    #[arg(long, value_name = "HOST")]
+    #[arg(long, value_name = "HOST", value_parser = crate::stdio::server::validate_host)]
     pub host: Option<String>,
 
+    /// Prefer IPv6 loopback (::1) for local category servers
+    /// Only affects the default host with --no-tls; an explicit --host wins
+    #[arg(long)]
+    pub ipv6: bool,
+
     /// Disable TLS for HTTP connections (use HTTP instead of HTTPS)
     /// Useful for local testing without certificates
     #[arg(long)]
@@ -194,6 +199,46 @@ pub struct Cli {
     #[arg(long, env = "SSH_PASSWORD")]
     pub ssh_password: Option<String>,
     
+    /// Order list_tools results by recent usage in this workspace
+    ///
+    /// Tools that were called most often (recently) and most reliably are
+    /// listed first, which helps clients that only keep the first N tools.
+    /// Usage is tracked per workspace (git root or working directory) and
+    /// persisted across restarts. Not available with --tool-groups.
+    #[arg(long, conflicts_with = "tool_groups")]
+    pub order_tools_by_usage: bool,
+
+    /// Expose one gateway tool per category instead of every tool
+    ///
+    /// For clients that cap the number of tools a server may expose. Each
+    /// gateway (e.g. `git_tools`) lists, describes, and calls the tools in its
+    /// category, so all tools stay reachable through a handful of entries.
+    #[arg(long)]
+    pub tool_groups: bool,
+
+    /// Record every tool call and its result to a JSONL file
+    ///
+    /// The recording can be served back with `--replay` for deterministic,
+    /// server-free regression runs of an agent workflow.
+    #[arg(long, value_name = "FILE")]
+    pub record: Option<std::path::PathBuf>,
+
+    /// Serve tool results from a `--record` file instead of the category servers
+    ///
+    /// Calls must arrive in the recorded order with the recorded arguments;
+    /// the first divergent call (and every call after it) fails.
+    #[arg(long, value_name = "FILE", conflicts_with = "record")]
+    pub replay: Option<std::path::PathBuf>,
+
+    /// Inject latency, dropped connections, and errors into tool calls
+    ///
+    /// Rules are read from a JSON file and matched per tool or category, for
+    /// testing agent retry handling. Only available in builds with the
+    /// `chaos` feature.
+    #[cfg(feature = "chaos")]
+    #[arg(long, value_name = "FILE")]
+    pub chaos: Option<std::path::PathBuf>,
+
     /// Enable automatic config file watching and hot-reload
     ///
     /// When enabled, the server will monitor the config file for changes
@@ -268,19 +313,94 @@ pub enum Commands {
         passthrough_args: Vec<String>,
     },
 
+    /// Call a single tool and print the result as JSON
+    ///
+    /// Connects to the category server that owns the tool (honoring --host,
+    /// --no-tls and the --http-* connection flags) and exits with code 1 if
+    /// the tool returns an error result.
+    ///
+    /// Example: kodegen call fs_read_file --args '{"path": "Cargo.toml"}'
+    /// Example: echo '{"path": "Cargo.toml"}' | kodegen call fs_read_file
+    Call {
+        /// Tool name (see --list-tools)
+        tool: String,
+
+        /// Tool arguments as a JSON object (read from stdin if omitted)
+        #[arg(long, value_name = "JSON")]
+        args: Option<String>,
+    },
+
     /// Hook utilities for Claude Code plugin integration
     Hook {
         #[command(subcommand)]
         hook_command: HookCommands,
     },
+
+    /// Developer utilities for working on kodegen tools
+    Dev {
+        #[command(subcommand)]
+        dev_command: DevCommands,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum DevCommands {
+    /// Scaffold a new tool (tool module, schema module, registration steps)
+    ///
+    /// Example: kodegen dev new-tool git git_blame_line
+    NewTool {
+        /// Category the tool belongs to (see --list-categories)
+        category: String,
+
+        /// Tool name in snake_case (e.g. git_blame_line)
+        name: String,
+
+        /// Directory to write the generated modules to
+        #[arg(long, default_value = ".")]
+        out_dir: std::path::PathBuf,
+
+        /// Overwrite existing files
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Check tool metadata against the port table and bundled toolsets
+    ///
+    /// Exits with code 1 if any tool cannot be routed, has an invalid args
+    /// schema, or is referenced by a toolset without being registered.
+    VerifyMetadata,
 }
 
 #[derive(Subcommand, Debug)]
 pub enum HookCommands {
     /// Handle PostToolUse hook events (runs after tool execution completes)
-    PostToolUse,
+    PostToolUse {
+        /// Run the language formatter (rustfmt, gofmt, ruff, prettier) on files
+        /// touched by edit tools and report reformatting to Claude
+        #[arg(long)]
+        format: bool,
+    },
     /// Handle Stop hook events (runs when Claude Code finishes responding)
-    Stop,
+    ///
+    /// Continuation policies are opt-in; when one is violated the hook
+    /// returns decision=block so Claude keeps working instead of stopping.
+    Stop {
+        /// Block stopping while the git working tree has uncommitted changes
+        #[arg(long)]
+        require_clean_tree: bool,
+
+        /// Block stopping when files were edited after the last test run
+        #[arg(long)]
+        require_tests: bool,
+
+        /// Command substrings that count as a test run (comma-separated)
+        #[arg(
+            long = "test-pattern",
+            value_delimiter = ',',
+            default_value = "cargo test,cargo nextest,npm test,pnpm test,yarn test,pytest,go test"
+        )]
+        test_patterns: Vec<String>,
+    },
 }
 
 impl Cli {
@@ -339,11 +459,14 @@ impl Cli {
     ///
     /// Returns:
     /// - Explicit --host value if provided
+    /// - "::1" if --no-tls and --ipv6 are set without explicit --host
     /// - "127.0.0.1" if --no-tls is set without explicit --host (local testing)
     /// - "mcp.kodegen.ai" otherwise (production default)
     pub fn effective_host(&self) -> &str {
         if let Some(ref host) = self.host {
             host
+        } else if self.no_tls && self.ipv6 {
+            "::1"
         } else if self.no_tls {
             "127.0.0.1"
         } else {
@@ -0,0 +1,130 @@
+//! Invoke a single tool from the command line
+//!
+//! Connects directly to the category server that owns the tool, calls it once
+//! with JSON arguments, and prints the `CallToolResult` as JSON on stdout.
+//! Intended for shell scripts and CI jobs that want to reuse kodegen tools
+//! without an MCP client.
+
+use anyhow::{Context, Result};
+use std::io::{IsTerminal, Read};
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+use crate::stdio::HttpConnectionConfig;
+use crate::stdio::metadata::get_routing_table;
+use crate::stdio::server::{category_base_url, connect_with_retry, notify_connection_drop};
+
+/// Handle the `kodegen call` subcommand
+///
+/// Arguments are taken from `--args` when given, otherwise read from stdin
+/// (an interactive terminal on stdin means "no arguments").
+/// Returns false when the tool reports an error result, so the caller can
+/// exit non-zero after the connection has been cleaned up.
+pub async fn handle_call(
+    tool: String,
+    args: Option<String>,
+    http_config: HttpConnectionConfig,
+) -> Result<bool> {
+    let (category, port) = get_routing_table().get(tool.as_str()).copied().ok_or_else(|| {
+        anyhow::anyhow!("Unknown tool: {} (use --list-tools to see available tools)", tool)
+    })?;
+
+    let args = parse_args(args)?;
+
+    let url = format!("{}/mcp", category_base_url(&http_config, port));
+    log::debug!("Calling tool '{}' on {} server at {}", tool, category, url);
+
+    let connection_id = Uuid::new_v4().to_string();
+    let (client, connection) = connect_with_retry(
+        &url,
+        &connection_id,
+        http_config.max_retries,
+        http_config.retry_backoff,
+        http_config.connection_timeout,
+        &CancellationToken::new(),
+    )
+    .await
+    .with_context(|| format!("Failed to connect to {} server", category))?;
+
+    // Use 15-minute timeout like the proxy - backend servers handle their own timeouts
+    let client = client.with_timeout(Duration::from_secs(900));
+
+    let result = client.call_tool(&tool, args).await;
+
+    // Release connection-scoped state (terminals, browser sessions) whatever the outcome
+    notify_connection_drop(&http_config, category, port, &connection_id).await;
+    drop(client);
+    drop(connection);
+
+    let result = result.map_err(|e| anyhow::anyhow!("Tool call '{}' failed: {}", tool, e))?;
+
+    println!("{}", serde_json::to_string_pretty(&result)?);
+
+    Ok(!result.is_error.unwrap_or(false))
+}
+
+/// Resolve tool arguments from the `--args` flag or stdin
+fn parse_args(args: Option<String>) -> Result<serde_json::Value> {
+    let raw = match args {
+        Some(raw) => raw,
+        None if std::io::stdin().is_terminal() => String::new(),
+        None => {
+            let mut buf = String::new();
+            std::io::stdin()
+                .read_to_string(&mut buf)
+                .context("Failed to read tool arguments from stdin")?;
+            buf
+        }
+    };
+
+    parse_args_json(&raw)
+}
+
+/// Parse raw tool arguments (empty input means no arguments)
+fn parse_args_json(raw: &str) -> Result<serde_json::Value> {
+    if raw.trim().is_empty() {
+        return Ok(serde_json::Value::Object(serde_json::Map::new()));
+    }
+
+    let value: serde_json::Value =
+        serde_json::from_str(raw).context("Tool arguments must be valid JSON")?;
+
+    if !value.is_object() {
+        anyhow::bail!("Tool arguments must be a JSON object");
+    }
+
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_args_empty_is_empty_object() {
+        assert_eq!(parse_args_json("").unwrap(), serde_json::json!({}));
+        assert_eq!(parse_args_json("  \n").unwrap(), serde_json::json!({}));
+        assert_eq!(parse_args(Some(String::new())).unwrap(), serde_json::json!({}));
+    }
+
+    #[test]
+    fn test_parse_args_object() {
+        let value = parse_args(Some(r#"{"path": "README.md"}"#.to_string())).unwrap();
+        assert_eq!(value, serde_json::json!({ "path": "README.md" }));
+    }
+
+    #[test]
+    fn test_parse_args_rejects_non_object() {
+        for raw in ["[1, 2]", "\"README.md\"", "42", "null"] {
+            let err = parse_args_json(raw).unwrap_err();
+            assert!(err.to_string().contains("JSON object"), "{raw}: {err}");
+        }
+    }
+
+    #[test]
+    fn test_parse_args_rejects_invalid_json() {
+        let err = parse_args_json("{path: README.md}").unwrap_err();
+        assert!(err.to_string().contains("valid JSON"));
+    }
+}
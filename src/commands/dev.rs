@@ -0,0 +1,341 @@
+//! Developer utilities (`kodegen dev ...`)
+//!
+//! `kodegen dev new-tool <category> <name>` scaffolds a new tool so the
+//! pieces that used to be written by hand start out consistent:
+//! - `{name}.rs` - tool module (Tool impl, execute, prompt) for the category's tool crate
+//! - `{name}_schema.rs` - schema module (name constant, Args/Output/PromptArgs
+//!   structs, ToolMetadata registration) for kodegen-mcp-schema
+//!
+//! The remaining wiring (mod declarations, category server registration) is
+//! printed as a diff to apply by hand. Routing, `--list-tools`, and the stdio
+//! proxy pick the tool up automatically from the ToolMetadata inventory.
+//!
+//! `kodegen dev verify-metadata` checks that the inventory, the category port
+//! table, and the bundled toolsets agree.
+
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+use crate::stdio::metadata::{get_routing_table, verify_metadata, CATEGORY_PORTS};
+
+/// Tool module template (category tool crate)
+const TOOL_TEMPLATE: &str = r#"//! {{name}} tool
+
+use kodegen_mcp_schema::{{category}}::{{{args}}, {{output}}, {{prompt_args}}, {{const_name}}};
+use kodegen_mcp_schema::{McpError, Tool, ToolExecutionContext, ToolResponse};
+use rmcp::model::{PromptArgument, PromptMessage, PromptMessageContent, PromptMessageRole};
+
+#[derive(Clone, Default)]
+pub struct {{tool}};
+
+impl {{tool}} {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Tool for {{tool}} {
+    type Args = {{args}};
+    type PromptArgs = {{prompt_args}};
+
+    fn name() -> &'static str {
+        {{const_name}}
+    }
+
+    fn description() -> &'static str {
+        "TODO: describe what {{name}} does and when to use it"
+    }
+
+    fn read_only() -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        _ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<{{output}}>, McpError> {
+        let _ = args;
+        // Fail the call instead of panicking the category server until this is implemented
+        Err(McpError::Other(anyhow::anyhow!("{{name}} is not implemented yet")))
+    }
+
+    fn prompt_arguments() -> Vec<PromptArgument> {
+        Vec::new()
+    }
+
+    async fn prompt(&self, _args: Self::PromptArgs) -> Result<Vec<PromptMessage>, McpError> {
+        Ok(vec![PromptMessage {
+            role: PromptMessageRole::User,
+            content: PromptMessageContent::text("How do I use {{name}}?"),
+        }])
+    }
+}
+"#;
+
+/// Schema module template (kodegen-mcp-schema, under the category module)
+const SCHEMA_TEMPLATE: &str = r#"//! {{name}} schema
+
+use rmcp::model::{PromptArgument, PromptMessage};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::ToolMetadata;
+
+/// Canonical tool name
+pub const {{const_name}}: &str = "{{name}}";
+
+/// Arguments for {{name}}
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct {{args}} {
+    // TODO: add arguments (doc comments become schema descriptions)
+}
+
+/// Structured output of {{name}}
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct {{output}} {
+    // TODO: add output fields
+}
+
+/// Prompt arguments for {{name}}
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
+pub struct {{prompt_args}} {}
+
+fn {{name}}_args_schema() -> serde_json::Value {
+    serde_json::to_value(schemars::schema_for!({{args}})).unwrap_or_default()
+}
+
+fn {{name}}_prompt_arguments() -> Vec<PromptArgument> {
+    Vec::new()
+}
+
+fn {{name}}_generate_prompts(_args: &serde_json::Value) -> Vec<PromptMessage> {
+    Vec::new()
+}
+
+inventory::submit! {
+    ToolMetadata {
+        name: {{const_name}},
+        category: &kodegen_config::{{category_const}},
+        description: "TODO: describe what {{name}} does and when to use it",
+        args_schema: {{name}}_args_schema,
+        prompt_arguments: {{name}}_prompt_arguments,
+        generate_prompts: {{name}}_generate_prompts,
+    }
+}
+"#;
+
+/// Handle `kodegen dev new-tool`
+///
+/// Writes the tool and schema modules into `out_dir` and prints the wiring
+/// that still has to be added by hand. Refuses to overwrite existing files
+/// unless `force` is set.
+pub fn handle_new_tool(category: String, name: String, out_dir: PathBuf, force: bool) -> Result<()> {
+    if !CATEGORY_PORTS.iter().any(|(cat, _port)| cat.name == category) {
+        let known: Vec<&str> = CATEGORY_PORTS.iter().map(|(cat, _port)| cat.name).collect();
+        anyhow::bail!("Unknown category: {} (known: {})", category, known.join(", "));
+    }
+
+    validate_tool_name(&name)?;
+
+    if get_routing_table().contains_key(name.as_str()) {
+        anyhow::bail!("Tool '{}' already exists", name);
+    }
+
+    let names = ScaffoldNames::new(&category, &name);
+
+    let tool_path = out_dir.join(format!("{}.rs", name));
+    let schema_path = out_dir.join(format!("{}_schema.rs", name));
+
+    for path in [&tool_path, &schema_path] {
+        if path.exists() && !force {
+            anyhow::bail!("{} already exists (use --force to overwrite)", path.display());
+        }
+    }
+
+    std::fs::create_dir_all(&out_dir)
+        .with_context(|| format!("Failed to create {}", out_dir.display()))?;
+    write_file(&tool_path, &names.render(TOOL_TEMPLATE))?;
+    write_file(&schema_path, &names.render(SCHEMA_TEMPLATE))?;
+
+    println!("Created {}", tool_path.display());
+    println!("Created {}", schema_path.display());
+    println!();
+    println!("Remaining wiring:");
+    println!();
+    println!(
+        "1. kodegen-mcp-schema: move {} to src/{}/{}.rs, then in src/{}/mod.rs:",
+        schema_path.display(),
+        category,
+        name,
+        category
+    );
+    println!("+ mod {};", name);
+    println!("+ pub use {}::*;", name);
+    println!();
+    println!(
+        "2. Tool crate for '{}': move {} to src/{}.rs, then in src/lib.rs:",
+        category,
+        tool_path.display(),
+        name
+    );
+    println!("+ mod {};", name);
+    println!("+ pub use {}::{};", name, names.tool);
+    println!();
+    println!("3. Category server for '{}': register the tool with the others:", category);
+    println!("+ .register({}::new())", names.tool);
+    println!();
+    println!(
+        "4. Replace the TODO descriptions and implement execute (it returns a \"not implemented\" error until then); `kodegen dev verify-metadata` fails while a TODO description remains."
+    );
+    println!();
+    println!(
+        "Routing, --list-tools, and the stdio proxy pick '{}' up from the ToolMetadata inventory.",
+        name
+    );
+
+    Ok(())
+}
+
+/// Handle `kodegen dev verify-metadata`
+///
+/// Prints every inconsistency found. Returns false if there are any, so the
+/// caller can exit non-zero.
+pub fn handle_verify_metadata() -> Result<bool> {
+    let problems = verify_metadata();
+
+    if problems.is_empty() {
+        println!(
+            "Metadata OK: {} tools across {} categories",
+            inventory::iter::<kodegen_mcp_schema::ToolMetadata>().count(),
+            CATEGORY_PORTS.len()
+        );
+        return Ok(true);
+    }
+
+    eprintln!("Found {} metadata problem(s):", problems.len());
+    for problem in &problems {
+        eprintln!("  - {}", problem);
+    }
+    Ok(false)
+}
+
+/// Require a snake_case tool name (lowercase letters, digits, underscores)
+fn validate_tool_name(name: &str) -> Result<()> {
+    let valid = name.chars().next().is_some_and(|c| c.is_ascii_lowercase())
+        && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        && !name.ends_with('_')
+        && !name.contains("__");
+
+    if !valid {
+        anyhow::bail!("Tool name must be snake_case (e.g. fs_read_file), got '{}'", name);
+    }
+    Ok(())
+}
+
+fn write_file(path: &Path, content: &str) -> Result<()> {
+    std::fs::write(path, content).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Identifiers derived from the category and tool name
+#[derive(Debug)]
+struct ScaffoldNames {
+    name: String,
+    category: String,
+    category_const: String,
+    const_name: String,
+    tool: String,
+    args: String,
+    output: String,
+    prompt_args: String,
+}
+
+impl ScaffoldNames {
+    fn new(category: &str, name: &str) -> Self {
+        let pascal: String = name
+            .split('_')
+            .map(|word| {
+                let mut chars = word.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                    .unwrap_or_default()
+            })
+            .collect();
+
+        Self {
+            name: name.to_string(),
+            category: category.replace('-', "_"),
+            category_const: format!("CATEGORY_{}", category.replace('-', "_").to_ascii_uppercase()),
+            const_name: name.to_ascii_uppercase(),
+            tool: format!("{}Tool", pascal),
+            args: format!("{}Args", pascal),
+            output: format!("{}Output", pascal),
+            prompt_args: format!("{}PromptArgs", pascal),
+        }
+    }
+
+    /// Substitute `{{placeholders}}` in a template
+    fn render(&self, template: &str) -> String {
+        [
+            ("{{name}}", &self.name),
+            ("{{category}}", &self.category),
+            ("{{category_const}}", &self.category_const),
+            ("{{const_name}}", &self.const_name),
+            ("{{tool}}", &self.tool),
+            ("{{args}}", &self.args),
+            ("{{output}}", &self.output),
+            ("{{prompt_args}}", &self.prompt_args),
+        ]
+        .iter()
+        .fold(template.to_string(), |text, (placeholder, value)| text.replace(placeholder, value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scaffold_names() {
+        let names = ScaffoldNames::new("git", "git_blame_line");
+
+        assert_eq!(names.tool, "GitBlameLineTool");
+        assert_eq!(names.args, "GitBlameLineArgs");
+        assert_eq!(names.const_name, "GIT_BLAME_LINE");
+        assert_eq!(names.category_const, "CATEGORY_GIT");
+    }
+
+    #[test]
+    fn test_render_leaves_no_placeholders() {
+        let names = ScaffoldNames::new("git", "git_blame_line");
+
+        for template in [TOOL_TEMPLATE, SCHEMA_TEMPLATE] {
+            let rendered = names.render(template);
+            assert!(!rendered.contains("{{"), "unrendered placeholder in:\n{rendered}");
+        }
+        assert!(names.render(TOOL_TEMPLATE).contains("use kodegen_mcp_schema::git::{GitBlameLineArgs,"));
+    }
+
+    #[test]
+    fn test_scaffold_does_not_panic_or_pass_verification() {
+        let names = ScaffoldNames::new("git", "git_blame_line");
+
+        let tool = names.render(TOOL_TEMPLATE);
+        assert!(!tool.contains("todo!") && !tool.contains("unimplemented!"));
+        assert!(tool.contains("git_blame_line is not implemented yet"));
+
+        // verify-metadata rejects the placeholder until it is replaced
+        let schema = names.render(SCHEMA_TEMPLATE);
+        assert!(schema.contains("description: \"TODO: describe what git_blame_line does"));
+    }
+
+    #[test]
+    fn test_validate_tool_name() {
+        assert!(validate_tool_name("git_blame_line").is_ok());
+        assert!(validate_tool_name("GitBlame").is_err());
+        assert!(validate_tool_name("git-blame").is_err());
+        assert!(validate_tool_name("git__blame").is_err());
+        assert!(validate_tool_name("").is_err());
+    }
+}
@@ -1,7 +1,11 @@
 pub mod monitor;
 pub mod claude;
 pub mod plugin;
+pub mod call;
+pub mod dev;
 
 pub use monitor::handle_monitor;
 pub use claude::handle_claude;
 pub use plugin::ensure_plugin_configured;
+pub use call::handle_call;
+pub use dev::{handle_new_tool, handle_verify_metadata};
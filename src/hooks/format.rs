@@ -0,0 +1,260 @@
+//! Format-on-edit support for the PostToolUse hook
+//!
+//! When enabled with `kodegen hook post-tool-use --format`, files touched by
+//! filesystem edit tools are run through the formatter for their language and
+//! the outcome is reported back to Claude as `additionalContext`.
+//!
+//! The file content is piped through the formatter on stdin and the result is
+//! written back, so only the edited file changes. (`rustfmt lib.rs` would also
+//! rewrite every out-of-line child module without telling Claude.)
+
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use super::PostToolUseInput;
+
+/// Kodegen edit tools (canonical names) whose `path` argument names the edited file
+const KODEGEN_EDIT_TOOLS: &[&str] = &["fs_write_file", "fs_edit_block"];
+
+/// Claude Code built-in edit tools whose `file_path` argument names the edited file
+const NATIVE_EDIT_TOOLS: &[&str] = &["Edit", "MultiEdit", "Write"];
+
+/// Maximum time a formatter may run before it is abandoned
+const FORMAT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Format the file edited by this tool call, if any
+///
+/// Returns a short description for `additionalContext` when the file was
+/// reformatted or the formatter rejected it, and None when nothing happened
+/// (not an edit, unknown language, formatter not installed, already formatted).
+pub async fn format_edited_file(input: &PostToolUseInput) -> Option<String> {
+    if input.is_tool_error() {
+        return None;
+    }
+
+    let path = edited_path(input)?;
+    let (formatter, args) = formatter_for(&path)?;
+
+    let Ok(formatter_bin) = which::which(formatter) else {
+        log::debug!("Formatter '{}' not found in PATH, skipping {}", formatter, path.display());
+        return None;
+    };
+
+    let before = tokio::fs::read(&path).await.ok()?;
+
+    // Run next to the file so formatter config (rustfmt.toml, .prettierrc) is found
+    let dir = path.parent().map(Path::to_path_buf).unwrap_or_else(|| PathBuf::from(&input.cwd));
+
+    let mut cmd = Command::new(formatter_bin);
+    cmd.args(&args)
+        .current_dir(dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+
+    let mut child = match cmd.spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            log::warn!("Failed to run {} on {}: {}", formatter, path.display(), e);
+            return None;
+        }
+    };
+
+    // Feed stdin from a separate task so a full stdout pipe cannot deadlock us
+    let mut stdin = child.stdin.take()?;
+    let content = before.clone();
+    tokio::spawn(async move {
+        let _ = stdin.write_all(&content).await;
+    });
+
+    let output = match tokio::time::timeout(FORMAT_TIMEOUT, child.wait_with_output()).await {
+        Ok(Ok(output)) => output,
+        Ok(Err(e)) => {
+            log::warn!("Failed to run {} on {}: {}", formatter, path.display(), e);
+            return None;
+        }
+        Err(_) => {
+            log::warn!("{} timed out on {}", formatter, path.display());
+            return None;
+        }
+    };
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let first_lines: Vec<&str> = stderr.lines().take(5).collect();
+        return Some(format!(
+            "{} could not format {}: {}",
+            formatter,
+            path.display(),
+            first_lines.join(" | ")
+        ));
+    }
+
+    // Never replace a non-empty file with nothing
+    if output.stdout == before || (output.stdout.is_empty() && !before.is_empty()) {
+        return None;
+    }
+
+    if let Err(e) = tokio::fs::write(&path, &output.stdout).await {
+        log::warn!("Failed to write formatted {}: {}", path.display(), e);
+        return None;
+    }
+
+    Some(format!(
+        "{} was reformatted with {}. Re-read the file before making further edits.",
+        path.display(),
+        formatter
+    ))
+}
+
+/// Resolve the absolute path of the file edited by this tool call
+fn edited_path(input: &PostToolUseInput) -> Option<PathBuf> {
+    let key = match input.canonical_tool_name() {
+        Some(name) if KODEGEN_EDIT_TOOLS.contains(&name) => "path",
+        Some(_) => return None,
+        None if NATIVE_EDIT_TOOLS.contains(&input.tool_name.as_str()) => "file_path",
+        None => return None,
+    };
+
+    let raw = input.tool_input.get(key)?.as_str()?;
+    let path = Path::new(raw);
+
+    Some(if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        Path::new(&input.cwd).join(path)
+    })
+}
+
+/// Pick the formatter command and arguments for a file based on its extension
+///
+/// Every formatter reads the content on stdin and writes the result to stdout;
+/// the path is passed only where the formatter uses it to pick a parser.
+fn formatter_for(path: &Path) -> Option<(&'static str, Vec<String>)> {
+    let extension = path.extension()?.to_str()?;
+    let file = path.to_string_lossy().into_owned();
+
+    match extension {
+        "rs" => Some(("rustfmt", vec!["--edition".to_string(), rust_edition(path)])),
+        "go" => Some(("gofmt", Vec::new())),
+        "py" => Some((
+            "ruff",
+            vec!["format".to_string(), "--quiet".to_string(), "--stdin-filename".to_string(), file],
+        )),
+        "js" | "jsx" | "ts" | "tsx" | "mjs" | "cjs" | "css" | "scss" | "html" | "vue" => Some((
+            "prettier",
+            vec!["--stdin-filepath".to_string(), file, "--log-level".to_string(), "warn".to_string()],
+        )),
+        _ => None,
+    }
+}
+
+/// Find the Rust edition from the nearest Cargo.toml above `path` (default: 2021)
+fn rust_edition(path: &Path) -> String {
+    for dir in path.ancestors().skip(1) {
+        let Ok(manifest) = std::fs::read_to_string(dir.join("Cargo.toml")) else {
+            continue;
+        };
+
+        let edition = manifest.lines().find_map(|line| {
+            let value = line.trim().strip_prefix("edition")?.trim_start().strip_prefix('=')?;
+            Some(value.trim().trim_matches('"').to_string())
+        });
+
+        if let Some(edition) = edition {
+            return edition;
+        }
+    }
+
+    "2021".to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn input(tool_name: &str, tool_input: serde_json::Value) -> PostToolUseInput {
+        PostToolUseInput {
+            session_id: "session".to_string(),
+            transcript_path: "/tmp/transcript.jsonl".to_string(),
+            cwd: "/work".to_string(),
+            permission_mode: "default".to_string(),
+            hook_event_name: "PostToolUse".to_string(),
+            tool_name: tool_name.to_string(),
+            tool_input,
+            tool_response: json!({ "success": true }),
+            tool_use_id: "toolu_1".to_string(),
+        }
+    }
+
+    /// Fresh directory under the system temp dir
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("kodegen-format-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_edited_path() {
+        let kodegen = input("mcp__plugin_kodegen_kodegen__fs_edit_block", json!({ "path": "src/lib.rs" }));
+        assert_eq!(edited_path(&kodegen), Some(PathBuf::from("/work/src/lib.rs")));
+
+        let native = input("Write", json!({ "file_path": "/abs/main.go" }));
+        assert_eq!(edited_path(&native), Some(PathBuf::from("/abs/main.go")));
+
+        // Non-edit tools are ignored, even with a path argument
+        let read = input("mcp__plugin_kodegen_kodegen__fs_read_file", json!({ "path": "src/lib.rs" }));
+        assert_eq!(edited_path(&read), None);
+        assert_eq!(edited_path(&input("Read", json!({ "file_path": "/abs/main.go" }))), None);
+    }
+
+    #[test]
+    fn test_formatter_for() {
+        let (formatter, args) = formatter_for(Path::new("/nonexistent/a.py")).unwrap();
+        assert_eq!(formatter, "ruff");
+        assert_eq!(args, ["format", "--quiet", "--stdin-filename", "/nonexistent/a.py"]);
+
+        assert_eq!(formatter_for(Path::new("/nonexistent/main.go")).unwrap().0, "gofmt");
+        assert_eq!(formatter_for(Path::new("/nonexistent/app.tsx")).unwrap().0, "prettier");
+        assert_eq!(formatter_for(Path::new("/nonexistent/README.md")), None);
+        assert_eq!(formatter_for(Path::new("/nonexistent/Makefile")), None);
+    }
+
+    #[test]
+    fn test_rust_edition() {
+        let root = temp_dir();
+        let member = root.join("crates/member");
+        std::fs::create_dir_all(member.join("src")).unwrap();
+
+        std::fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"crates/member\"]\n\n[workspace.package]\nedition = \"2024\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            member.join("Cargo.toml"),
+            "[package]\nname = \"member\"\nedition.workspace = true\n",
+        )
+        .unwrap();
+
+        // edition.workspace = true falls through to the workspace manifest
+        assert_eq!(rust_edition(&member.join("src/lib.rs")), "2024");
+
+        std::fs::write(member.join("Cargo.toml"), "[package]\nname = \"member\"\nedition = \"2018\"\n").unwrap();
+        assert_eq!(rust_edition(&member.join("src/lib.rs")), "2018");
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_rust_edition_default() {
+        let dir = temp_dir();
+        assert_eq!(rust_edition(&dir.join("main.rs")), "2021");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
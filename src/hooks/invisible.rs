@@ -0,0 +1,370 @@
+//! Suspicious character warnings for the PostToolUse hook
+//!
+//! Content written by edit tools is scanned for characters that render
+//! invisibly or deceptively: bidirectional controls (Trojan Source), zero-width
+//! and other invisible format characters, and words mixing Latin letters with
+//! look-alike Cyrillic or Greek ones. Findings are reported back to Claude as
+//! `additionalContext` so they can be removed or confirmed as intentional.
+
+use serde_json::Value;
+
+use super::PostToolUseInput;
+
+/// Maximum number of individual findings listed in the warning
+const MAX_EXAMPLES: usize = 5;
+
+/// Zero-width joiner, legitimate between emoji (woman + ZWJ + laptop) but invisible anywhere else
+const ZWJ: char = '\u{200D}';
+
+/// Zero-width non-joiner, legitimate inside Persian and Indic words
+const ZWNJ: char = '\u{200C}';
+
+/// Black flag, the base of emoji tag sequences (England, Scotland, Wales flags)
+const BLACK_FLAG: char = '\u{1F3F4}';
+
+/// Cancel tag terminating an emoji tag sequence
+const CANCEL_TAG: char = '\u{E007F}';
+
+/// Non-Latin script blocks whose words may contain a zero-width non-joiner
+///
+/// A ZWNJ is only allowed between two characters of the same script.
+const ZWNJ_SCRIPTS: &[(char, char, &str)] = &[
+    ('\u{0590}', '\u{05FF}', "Hebrew"),
+    ('\u{0600}', '\u{06FF}', "Arabic"),
+    ('\u{0750}', '\u{077F}', "Arabic"),
+    ('\u{FB50}', '\u{FDFF}', "Arabic"),
+    ('\u{FE70}', '\u{FEFC}', "Arabic"),
+    ('\u{0700}', '\u{074F}', "Syriac"),
+    ('\u{0900}', '\u{097F}', "Devanagari"),
+    ('\u{0980}', '\u{09FF}', "Bengali"),
+    ('\u{0A00}', '\u{0A7F}', "Gurmukhi"),
+    ('\u{0A80}', '\u{0AFF}', "Gujarati"),
+    ('\u{0B00}', '\u{0B7F}', "Oriya"),
+    ('\u{0B80}', '\u{0BFF}', "Tamil"),
+    ('\u{0C00}', '\u{0C7F}', "Telugu"),
+    ('\u{0C80}', '\u{0CFF}', "Kannada"),
+    ('\u{0D00}', '\u{0D7F}', "Malayalam"),
+    ('\u{0D80}', '\u{0DFF}', "Sinhala"),
+];
+
+/// Cyrillic and Greek letters that render like Latin letters
+///
+/// Only these count towards mixed-script words, so notation such as `Δt` or
+/// `kΩ` is not flagged.
+const LATIN_LOOKALIKES: &[char] = &[
+    // Cyrillic lowercase: а е о р с у х і ј ѕ һ ӏ ԁ ԛ ԝ
+    '\u{0430}', '\u{0435}', '\u{043E}', '\u{0440}', '\u{0441}', '\u{0443}', '\u{0445}', '\u{0456}',
+    '\u{0458}', '\u{0455}', '\u{04BB}', '\u{04CF}', '\u{0501}', '\u{051B}', '\u{051D}',
+    // Cyrillic uppercase: А В Е К М Н О Р С Т Х І Ј Ѕ
+    '\u{0410}', '\u{0412}', '\u{0415}', '\u{041A}', '\u{041C}', '\u{041D}', '\u{041E}', '\u{0420}',
+    '\u{0421}', '\u{0422}', '\u{0425}', '\u{0406}', '\u{0408}', '\u{0405}',
+    // Greek lowercase: ο ν
+    '\u{03BF}', '\u{03BD}',
+    // Greek uppercase: Α Β Ε Ζ Η Ι Κ Μ Ν Ο Ρ Τ Υ Χ
+    '\u{0391}', '\u{0392}', '\u{0395}', '\u{0396}', '\u{0397}', '\u{0399}', '\u{039A}', '\u{039C}',
+    '\u{039D}', '\u{039F}', '\u{03A1}', '\u{03A4}', '\u{03A5}', '\u{03A7}',
+];
+
+/// Kind of suspicious character or word
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    /// Bidirectional override/isolate/mark
+    Bidi,
+    /// Zero-width or otherwise invisible format character
+    Invisible,
+    /// Word mixing Latin with look-alike Cyrillic or Greek letters
+    MixedScript,
+}
+
+impl Kind {
+    fn label(self) -> &'static str {
+        match self {
+            Kind::Bidi => "bidi control",
+            Kind::Invisible => "invisible character",
+            Kind::MixedScript => "mixed-script word",
+        }
+    }
+}
+
+/// A single finding with its 1-based line number
+#[derive(Debug, Clone, PartialEq)]
+struct Finding {
+    kind: Kind,
+    line: usize,
+    detail: String,
+}
+
+/// Check the content written by an edit tool call
+///
+/// Returns a warning for `additionalContext`, or None when the tool is not an
+/// edit tool or the written content is clean.
+pub fn check_written_content(input: &PostToolUseInput) -> Option<String> {
+    if input.is_tool_error() {
+        return None;
+    }
+
+    let texts = written_texts(input);
+    let findings: Vec<Finding> = texts.iter().flat_map(|text| scan(text)).collect();
+    if findings.is_empty() {
+        return None;
+    }
+
+    let path = input
+        .tool_input
+        .get("path")
+        .or_else(|| input.tool_input.get("file_path"))
+        .and_then(Value::as_str)
+        .unwrap_or("the edited file");
+
+    let mut summary = Vec::new();
+    for kind in [Kind::Bidi, Kind::Invisible, Kind::MixedScript] {
+        let count = findings.iter().filter(|f| f.kind == kind).count();
+        if count > 0 {
+            summary.push(format!("{} {}{}", count, kind.label(), if count == 1 { "" } else { "s" }));
+        }
+    }
+
+    let examples: Vec<String> = findings
+        .iter()
+        .take(MAX_EXAMPLES)
+        .map(|f| format!("line {}: {}", f.line, f.detail))
+        .collect();
+
+    Some(format!(
+        "Content written to {} contains suspicious characters ({}): {}. Remove them unless they are intentional.",
+        path,
+        summary.join(", "),
+        examples.join("; ")
+    ))
+}
+
+/// Extract the text an edit tool wrote (new content only, not what it replaced)
+fn written_texts(input: &PostToolUseInput) -> Vec<&str> {
+    let tool_input = &input.tool_input;
+    let field = |key: &str| tool_input.get(key).and_then(Value::as_str);
+
+    let name = input.canonical_tool_name().unwrap_or(input.tool_name.as_str());
+    match name {
+        "fs_write_file" | "Write" => field("content").into_iter().collect(),
+        "fs_edit_block" | "Edit" => field("new_string").into_iter().collect(),
+        "MultiEdit" => tool_input
+            .get("edits")
+            .and_then(Value::as_array)
+            .map(|edits| {
+                edits
+                    .iter()
+                    .filter_map(|edit| edit.get("new_string").and_then(Value::as_str))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
+
+/// Scan text for suspicious characters and mixed-script words
+fn scan(text: &str) -> Vec<Finding> {
+    let mut findings = Vec::new();
+
+    for (index, line) in text.lines().enumerate() {
+        let line_number = index + 1;
+
+        let chars: Vec<char> = line.chars().collect();
+
+        for (position, &c) in chars.iter().enumerate() {
+            // A byte order mark at the very start of a file is legitimate
+            if c == '\u{FEFF}' && index == 0 && position == 0 {
+                continue;
+            }
+
+            // So is a zero-width joiner inside an emoji sequence
+            if c == ZWJ
+                && position > 0
+                && is_emoji(chars[position - 1])
+                && chars.get(position + 1).is_some_and(|&next| is_emoji(next))
+            {
+                continue;
+            }
+
+            // ... a zero-width non-joiner inside a Persian or Indic word
+            if c == ZWNJ
+                && position > 0
+                && chars
+                    .get(position + 1)
+                    .is_some_and(|&next| same_zwnj_script(chars[position - 1], next))
+            {
+                continue;
+            }
+
+            // ... and the tag characters of a subdivision flag
+            if in_emoji_tag_sequence(&chars, position) {
+                continue;
+            }
+
+            if let Some(kind) = classify(c) {
+                findings.push(Finding {
+                    kind,
+                    line: line_number,
+                    detail: format!("U+{:04X}", c as u32),
+                });
+            }
+        }
+
+        for word in line.split(|c: char| !c.is_alphanumeric() && c != '_') {
+            if is_mixed_script(word) {
+                findings.push(Finding {
+                    kind: Kind::MixedScript,
+                    line: line_number,
+                    detail: format!("'{}'", word),
+                });
+            }
+        }
+    }
+
+    findings
+}
+
+/// Classify a single character
+fn classify(c: char) -> Option<Kind> {
+    match c {
+        '\u{061C}' | '\u{200E}' | '\u{200F}' | '\u{202A}'..='\u{202E}' | '\u{2066}'..='\u{2069}' => {
+            Some(Kind::Bidi)
+        }
+        '\u{00AD}' | '\u{180E}' | '\u{200B}'..='\u{200D}' | '\u{2060}'..='\u{2064}' | '\u{FEFF}'
+        | '\u{E0000}'..='\u{E007F}' => Some(Kind::Invisible),
+        _ => None,
+    }
+}
+
+/// Whether a character can appear on either side of a ZWJ in an emoji sequence
+///
+/// Covers pictographs, skin tone modifiers, gender and other symbols (♀ ⚕ ❤),
+/// and the emoji presentation selector that often precedes the joiner.
+fn is_emoji(c: char) -> bool {
+    matches!(
+        c,
+        '\u{1F000}'..='\u{1FAFF}' | '\u{2600}'..='\u{27BF}' | '\u{2B00}'..='\u{2BFF}' | '\u{FE0F}'
+    )
+}
+
+/// Whether two characters belong to the same script in `ZWNJ_SCRIPTS`
+fn same_zwnj_script(a: char, b: char) -> bool {
+    let script = |c: char| {
+        ZWNJ_SCRIPTS
+            .iter()
+            .find(|&&(first, last, _)| (first..=last).contains(&c))
+            .map(|&(_, _, name)| name)
+    };
+    script(a).is_some_and(|name| script(b) == Some(name))
+}
+
+/// Whether the tag character at `position` belongs to an emoji tag sequence
+///
+/// A tag sequence is the black flag followed by one or more tag characters
+/// (U+E0020..=U+E007E) and terminated by the cancel tag U+E007F.
+fn in_emoji_tag_sequence(chars: &[char], position: usize) -> bool {
+    let is_tag_spec = |c: &char| ('\u{E0020}'..='\u{E007E}').contains(c);
+    let c = chars[position];
+    if !is_tag_spec(&c) && c != CANCEL_TAG {
+        return false;
+    }
+
+    // Tag characters before this one, back to the base character
+    let preceding = chars[..position].iter().rev().take_while(|c| is_tag_spec(c)).count();
+    let base = position.checked_sub(preceding + 1).map(|index| chars[index]);
+
+    // The sequence must not be empty and must be terminated
+    let terminated = chars[position..].iter().find(|c| !is_tag_spec(c)) == Some(&CANCEL_TAG);
+    let non_empty = c != CANCEL_TAG || preceding > 0;
+
+    base == Some(BLACK_FLAG) && terminated && non_empty
+}
+
+/// Whether a word mixes ASCII Latin letters with look-alike Cyrillic or Greek letters
+fn is_mixed_script(word: &str) -> bool {
+    let latin = word.chars().any(|c| c.is_ascii_alphabetic());
+    let lookalike = word.chars().any(|c| LATIN_LOOKALIKES.contains(&c));
+    latin && lookalike
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(text: &str) -> Vec<Kind> {
+        scan(text).into_iter().map(|f| f.kind).collect()
+    }
+
+    #[test]
+    fn test_clean_text_has_no_findings() {
+        assert!(scan("fn main() {\n    println!(\"héllo, 世界 — Ω ≈ 1\");\n}\n").is_empty());
+    }
+
+    #[test]
+    fn test_detects_bidi_and_zero_width() {
+        let findings = scan("let ok = true;\nif admin\u{202E} {\nlet a\u{200B}b = 1;");
+
+        assert_eq!(findings.len(), 2);
+        assert_eq!(findings[0], Finding { kind: Kind::Bidi, line: 2, detail: "U+202E".to_string() });
+        assert_eq!(findings[1].kind, Kind::Invisible);
+        assert_eq!(findings[1].line, 3);
+    }
+
+    #[test]
+    fn test_leading_bom_is_allowed() {
+        assert!(scan("\u{FEFF}fn main() {}").is_empty());
+        assert_eq!(kinds("a\u{FEFF}b"), vec![Kind::Invisible]);
+    }
+
+    #[test]
+    fn test_detects_mixed_script_words() {
+        // Cyrillic 'а' (U+0430) inside an otherwise Latin identifier
+        assert_eq!(kinds("let p\u{0430}ypal = 1;"), vec![Kind::MixedScript]);
+        // Greek omicron in place of 'o'
+        assert_eq!(kinds("fn l\u{03BF}gin() {}"), vec![Kind::MixedScript]);
+        // Whole words in another script are fine
+        assert!(scan("// привет world").is_empty());
+    }
+
+    #[test]
+    fn test_greek_notation_is_not_mixed_script() {
+        assert!(scan("let Δt = 0.5; // 10 kΩ resistor, λmax, μs").is_empty());
+    }
+
+    #[test]
+    fn test_zwj_allowed_only_inside_emoji() {
+        // woman technologist, woman running, heart on fire, family
+        assert!(scan("Built by 👩\u{200D}💻 and 🏃\u{200D}♀\u{FE0F}").is_empty());
+        assert!(scan("❤\u{FE0F}\u{200D}🔥 👨\u{200D}👩\u{200D}👧").is_empty());
+
+        assert_eq!(kinds("let a\u{200D}b = 1;"), vec![Kind::Invisible]);
+        assert_eq!(kinds("👩\u{200D}x"), vec![Kind::Invisible]);
+        assert_eq!(kinds("\u{200D}💻"), vec![Kind::Invisible]);
+    }
+
+    #[test]
+    fn test_emoji_tag_sequences_are_allowed() {
+        // England (gbeng) and Scotland (gbsct) flags
+        let england = "\u{1F3F4}\u{E0067}\u{E0062}\u{E0065}\u{E006E}\u{E0067}\u{E007F}";
+        let scotland = "\u{1F3F4}\u{E0067}\u{E0062}\u{E0073}\u{E0063}\u{E0074}\u{E007F}";
+        assert!(scan(&format!("Made in {england} and {scotland}")).is_empty());
+
+        // Tags without the black flag, or without the cancel tag
+        assert_eq!(kinds("a\u{E0067}\u{E0062}\u{E007F}"), vec![Kind::Invisible; 3]);
+        assert_eq!(kinds("\u{1F3F4}\u{E0067}\u{E0062}"), vec![Kind::Invisible; 2]);
+        // A cancel tag with nothing to terminate
+        assert_eq!(kinds("\u{1F3F4}\u{E007F}"), vec![Kind::Invisible]);
+        assert_eq!(kinds(&format!("{england}\u{E007F}")), vec![Kind::Invisible]);
+    }
+
+    #[test]
+    fn test_zwnj_allowed_inside_same_script_words() {
+        // Persian "mikhaham" and Hindi with an explicit half form
+        assert!(scan("می\u{200C}خواهم").is_empty());
+        assert!(scan("क्\u{200C}ष").is_empty());
+
+        assert_eq!(kinds("let a\u{200C}b = 1;"), vec![Kind::Invisible]);
+        assert_eq!(kinds("م\u{200C}a"), vec![Kind::Invisible]);
+        assert_eq!(kinds("क\u{200C}ক"), vec![Kind::Invisible]);
+        assert_eq!(kinds("\u{200C}خ"), vec![Kind::Invisible]);
+    }
+}
@@ -1,3 +1,5 @@
+pub mod format;
+pub mod invisible;
 pub mod notify;
 pub mod stop;
 
@@ -49,6 +51,10 @@ pub struct StopInput {
     /// Path to the transcript JSONL file
     pub transcript_path: String,
 
+    /// Current working directory (empty if not provided)
+    #[serde(default)]
+    pub cwd: String,
+
     /// Permission mode: "default", "plan", "acceptEdits", or "bypassPermissions"
     pub permission_mode: String,
 
@@ -67,7 +73,6 @@ pub struct StopInput {
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Decision {
-    #[allow(dead_code)]
     Block,
 }
 
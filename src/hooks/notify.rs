@@ -10,7 +10,11 @@ use super::{PostToolUseInput, PostToolUseResponse, PostToolUseHookOutput};
 const LOGO_URL: &str = "https://kodegen.ai/assets/icon_128x128@2x.png";
 
 /// Run the notify hook for PostToolUse events
-pub async fn run() -> Result<()> {
+///
+/// Content written by edit tools is checked for invisible or look-alike
+/// characters, and when `format` is set the edited files are formatted first;
+/// both results are reported to Claude as additionalContext.
+pub async fn run(format: bool) -> Result<()> {
     let input: PostToolUseInput = serde_json::from_reader(std::io::stdin())?;
 
     // Validate hook event name
@@ -20,30 +24,25 @@ pub async fn run() -> Result<()> {
             input.hook_event_name,
             input.session_id
         );
-        let response = PostToolUseResponse {
-            decision: None,
-            reason: None,
-            hook_specific_output: Some(PostToolUseHookOutput {
-                hook_event_name: "PostToolUse".to_string(),
-                additional_context: None,
-            }),
-        };
-        println!("{}", serde_json::to_string(&response)?);
-        return Ok(());
+        return print_response(None);
     }
 
+    // Format edited files before anything else (covers Claude's built-in edit tools too)
+    let formatted = if format {
+        super::format::format_edited_file(&input).await
+    } else {
+        None
+    };
+    let suspicious = super::invisible::check_written_content(&input);
+
+    let additional_context = match (formatted, suspicious) {
+        (Some(formatted), Some(suspicious)) => Some(format!("{}\n{}", formatted, suspicious)),
+        (formatted, suspicious) => formatted.or(suspicious),
+    };
+
     // Only handle kodegen MCP tools
     if !input.is_kodegen_tool() {
-        let response = PostToolUseResponse {
-            decision: None,
-            reason: None,
-            hook_specific_output: Some(PostToolUseHookOutput {
-                hook_event_name: "PostToolUse".to_string(),
-                additional_context: None,
-            }),
-        };
-        println!("{}", serde_json::to_string(&response)?);
-        return Ok(());
+        return print_response(additional_context);
     }
 
     // Log tool execution for debugging
@@ -67,17 +66,7 @@ pub async fn run() -> Result<()> {
     };
 
     let Some((title, body_html)) = notification_data else {
-        // Output hook response to Claude Code
-        let response = PostToolUseResponse {
-            decision: None,
-            reason: None,
-            hook_specific_output: Some(PostToolUseHookOutput {
-                hook_event_name: "PostToolUse".to_string(),
-                additional_context: None,
-            }),
-        };
-        println!("{}", serde_json::to_string(&response)?);
-        return Ok(());
+        return print_response(additional_context);
     };
 
     let mut builder = NotificationBuilder::new()
@@ -101,13 +90,17 @@ pub async fn run() -> Result<()> {
     let _ = manager.send(notification).await;
     manager.shutdown().await;
 
-    // Output hook response to Claude Code
+    print_response(additional_context)
+}
+
+/// Output the hook response to Claude Code
+fn print_response(additional_context: Option<String>) -> Result<()> {
     let response = PostToolUseResponse {
         decision: None,
         reason: None,
         hook_specific_output: Some(PostToolUseHookOutput {
             hook_event_name: "PostToolUse".to_string(),
-            additional_context: None,
+            additional_context,
         }),
     };
     println!("{}", serde_json::to_string(&response)?);
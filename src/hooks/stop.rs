@@ -1,11 +1,42 @@
 use anyhow::Result;
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
 
-use super::{StopInput, StopResponse};
+use super::{Decision, StopInput, StopResponse};
+
+/// Tools whose use counts as a file edit (canonical names, MCP prefix stripped)
+const EDIT_TOOLS: &[&str] = &[
+    "Edit",
+    "MultiEdit",
+    "Write",
+    "NotebookEdit",
+    "fs_write_file",
+    "fs_edit_block",
+    "fs_move_file",
+    "fs_delete_file",
+];
+
+/// Tools that run shell commands (canonical names, MCP prefix stripped)
+const COMMAND_TOOLS: &[&str] = &["Bash", "terminal"];
+
+/// Continuation policies enforced by the stop hook
+///
+/// Each enabled policy can block Claude Code from stopping, returning
+/// `decision: "block"` with a reason that tells the agent what to do next.
+#[derive(Debug, Clone, Default)]
+pub struct StopPolicy {
+    /// Block while the git working tree has uncommitted changes
+    pub require_clean_tree: bool,
+    /// Block when files were edited after the last test run in the transcript
+    pub require_tests: bool,
+    /// Command substrings that identify a test run (e.g. "cargo test")
+    pub test_patterns: Vec<String>,
+}
 
 /// Run the stop hook for Stop events
 ///
 /// This hook is called when Claude Code finishes responding.
-pub async fn run() -> Result<()> {
+pub async fn run(policy: StopPolicy) -> Result<()> {
     let input: StopInput = serde_json::from_reader(std::io::stdin())?;
 
     // Validate hook event name
@@ -32,18 +63,183 @@ pub async fn run() -> Result<()> {
         input.stop_hook_active
     );
 
-    // Stop hook handler - currently just logs
-    // Future enhancements could include:
-    // - Send notification about session completion
-    // - Log session statistics
-    // - Cleanup temporary resources
+    // Claude is already continuing because of this hook - never block twice in a row,
+    // otherwise an unsatisfiable policy would keep the session running forever
+    let reason = if input.stop_hook_active {
+        None
+    } else {
+        evaluate_policy(&policy, &input).await
+    };
+
+    if let Some(ref reason) = reason {
+        log::info!("Stop hook blocking stop in session {}: {}", input.session_id, reason);
+    }
 
     // Output hook response to Claude Code
     let response = StopResponse {
-        decision: None,
-        reason: None,
+        decision: reason.as_ref().map(|_| Decision::Block),
+        reason,
     };
     println!("{}", serde_json::to_string(&response)?);
 
     Ok(())
 }
+
+/// Check every enabled policy and combine violations into a single reason
+async fn evaluate_policy(policy: &StopPolicy, input: &StopInput) -> Option<String> {
+    let mut violations = Vec::new();
+
+    if policy.require_tests {
+        match tokio::fs::read_to_string(&input.transcript_path).await {
+            Ok(transcript) => {
+                if edited_since_last_test(&transcript, &policy.test_patterns) {
+                    violations.push(format!(
+                        "Files were edited after the last test run. Run the tests ({}) before finishing.",
+                        policy.test_patterns.join(", ")
+                    ));
+                }
+            }
+            Err(e) => {
+                log::warn!(
+                    "Stop hook could not read transcript {}: {}",
+                    input.transcript_path,
+                    e
+                );
+            }
+        }
+    }
+
+    if policy.require_clean_tree {
+        let cwd = if input.cwd.is_empty() {
+            std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."))
+        } else {
+            PathBuf::from(&input.cwd)
+        };
+
+        if let Some(count) = uncommitted_changes(&cwd).await {
+            violations.push(format!(
+                "The working tree has {} uncommitted change{}. Commit or revert them before finishing.",
+                count,
+                if count == 1 { "" } else { "s" }
+            ));
+        }
+    }
+
+    if violations.is_empty() {
+        None
+    } else {
+        Some(violations.join(" "))
+    }
+}
+
+/// Count uncommitted changes reported by `git status --porcelain`
+///
+/// Returns None when the tree is clean, git is unavailable, or `cwd` is not
+/// inside a git repository.
+async fn uncommitted_changes(cwd: &Path) -> Option<usize> {
+    let output = Command::new("git")
+        .args(["status", "--porcelain"])
+        .current_dir(cwd)
+        .output()
+        .await
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    let count = String::from_utf8_lossy(&output.stdout).lines().count();
+    (count > 0).then_some(count)
+}
+
+/// Scan a transcript JSONL and report whether an edit happened after the last test run
+fn edited_since_last_test(transcript: &str, test_patterns: &[String]) -> bool {
+    let mut edited = false;
+
+    for line in transcript.lines() {
+        let Ok(entry) = serde_json::from_str::<serde_json::Value>(line) else {
+            continue;
+        };
+        let Some(content) = entry.pointer("/message/content").and_then(|c| c.as_array()) else {
+            continue;
+        };
+
+        for item in content {
+            if item.get("type").and_then(|t| t.as_str()) != Some("tool_use") {
+                continue;
+            }
+
+            // Strip MCP prefixes like "mcp__plugin_kodegen_kodegen__"
+            let name = item.get("name").and_then(|n| n.as_str()).unwrap_or_default();
+            let canonical = name.rsplit("__").next().unwrap_or(name);
+
+            if EDIT_TOOLS.contains(&canonical) {
+                edited = true;
+            } else if COMMAND_TOOLS.contains(&canonical) {
+                let command = item
+                    .pointer("/input/command")
+                    .and_then(|c| c.as_str())
+                    .unwrap_or_default();
+                if test_patterns.iter().any(|pattern| command.contains(pattern.as_str())) {
+                    edited = false;
+                }
+            }
+        }
+    }
+
+    edited
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool_use(name: &str, input: serde_json::Value) -> String {
+        serde_json::json!({
+            "type": "assistant",
+            "message": { "content": [{ "type": "tool_use", "name": name, "input": input }] }
+        })
+        .to_string()
+    }
+
+    fn patterns() -> Vec<String> {
+        vec!["cargo test".to_string()]
+    }
+
+    #[test]
+    fn test_edit_without_tests_is_flagged() {
+        let transcript = tool_use("Edit", serde_json::json!({ "file_path": "src/lib.rs" }));
+        assert!(edited_since_last_test(&transcript, &patterns()));
+    }
+
+    #[test]
+    fn test_tests_after_edit_clear_flag() {
+        let transcript = [
+            tool_use("mcp__plugin_kodegen_kodegen__fs_edit_block", serde_json::json!({})),
+            tool_use("mcp__plugin_kodegen_kodegen__terminal", serde_json::json!({ "command": "cargo test --workspace" })),
+        ]
+        .join("\n");
+        assert!(!edited_since_last_test(&transcript, &patterns()));
+    }
+
+    #[test]
+    fn test_edit_after_tests_is_flagged() {
+        let transcript = [
+            tool_use("Bash", serde_json::json!({ "command": "cargo test" })),
+            tool_use("Write", serde_json::json!({ "file_path": "README.md" })),
+            "not json".to_string(),
+        ]
+        .join("\n");
+        assert!(edited_since_last_test(&transcript, &patterns()));
+    }
+
+    #[test]
+    fn test_non_test_commands_do_not_count() {
+        let transcript = [
+            tool_use("Edit", serde_json::json!({})),
+            tool_use("Bash", serde_json::json!({ "command": "cargo build" })),
+        ]
+        .join("\n");
+        assert!(edited_since_last_test(&transcript, &patterns()));
+    }
+}
@@ -1,5 +1,6 @@
 use anyhow::Result;
 use clap::Parser;
+use std::process::ExitCode;
 
 mod cli;
 mod commands;
@@ -10,15 +11,15 @@ mod embedded;
 use cli::{Cli, Commands};
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> Result<ExitCode> {
     // Initialize logging
     env_logger::init();
 
     // Parse CLI arguments
-    let cli = Cli::parse();
+    let mut cli = Cli::parse();
 
     // Handle subcommands FIRST (before stdio server logic)
-    if let Some(command) = cli.command {
+    if let Some(command) = cli.command.take() {
         return match command {
             Commands::Install => {
                 println!("Install not yet implemented");
@@ -45,11 +46,45 @@ async fn main() -> Result<()> {
                 )
                 .await
             }
+            Commands::Call { tool, args } => {
+                let mut config_manager = kodegen_config_manager::ConfigManager::new();
+                config_manager.init().await?;
+
+                let http_config = http_connection_config(&cli, &config_manager);
+                let succeeded = commands::handle_call(tool, args, http_config).await?;
+
+                // A tool error result exits non-zero once the connection is cleaned up
+                return Ok(if succeeded { ExitCode::SUCCESS } else { ExitCode::FAILURE });
+            }
             Commands::Hook { hook_command } => match hook_command {
-                cli::HookCommands::PostToolUse => hooks::notify::run().await,
-                cli::HookCommands::Stop => hooks::stop::run().await,
+                cli::HookCommands::PostToolUse { format } => hooks::notify::run(format).await,
+                cli::HookCommands::Stop {
+                    require_clean_tree,
+                    require_tests,
+                    test_patterns,
+                } => {
+                    hooks::stop::run(hooks::stop::StopPolicy {
+                        require_clean_tree,
+                        require_tests,
+                        test_patterns,
+                    })
+                    .await
+                }
             },
-        };
+            Commands::Dev { dev_command } => match dev_command {
+                cli::DevCommands::NewTool {
+                    category,
+                    name,
+                    out_dir,
+                    force,
+                } => commands::handle_new_tool(category, name, out_dir, force),
+                cli::DevCommands::VerifyMetadata => {
+                    let consistent = commands::handle_verify_metadata()?;
+                    return Ok(if consistent { ExitCode::SUCCESS } else { ExitCode::FAILURE });
+                }
+            },
+        }
+        .map(|()| ExitCode::SUCCESS);
     }
 
     // Handle list-categories flag
@@ -58,7 +93,7 @@ async fn main() -> Result<()> {
         for category in cli::available_categories() {
             println!("  - {category}");
         }
-        return Ok(());
+        return Ok(ExitCode::SUCCESS);
     }
 
     // Handle list-tools flag
@@ -67,7 +102,7 @@ async fn main() -> Result<()> {
         for tool in cli::available_tools() {
             println!("  - {tool}");
         }
-        return Ok(());
+        return Ok(ExitCode::SUCCESS);
     }
 
     // Handle list-toolsets flag
@@ -76,7 +111,7 @@ async fn main() -> Result<()> {
         for toolset in embedded::list_toolsets() {
             println!("  - {}", toolset);
         }
-        return Ok(());
+        return Ok(ExitCode::SUCCESS);
     }
 
     // Get enabled tools from CLI (--tool/--tools/--toolset)
@@ -131,12 +166,16 @@ async fn main() -> Result<()> {
     });
 
     // Configure HTTP client connections to category servers
-    let http_config = stdio::HttpConnectionConfig {
-        connection_timeout: cli.http_connection_timeout(&config_manager),
-        max_retries: cli.http_max_retries(),
-        retry_backoff: cli.http_retry_backoff_duration(),
-        host: cli.effective_host().to_string(),
-        no_tls: cli.no_tls,
+    let http_config = http_connection_config(&cli, &config_manager);
+
+    // Optional proxy behaviors
+    let proxy_options = stdio::ProxyOptions {
+        order_tools_by_usage: cli.order_tools_by_usage,
+        tool_groups: cli.tool_groups,
+        record: cli.record.clone(),
+        replay: cli.replay.clone(),
+        #[cfg(feature = "chaos")]
+        chaos: cli.chaos.clone(),
     };
 
     // Create stdio proxy server (connects to category servers on ports 30437-30449)
@@ -144,6 +183,7 @@ async fn main() -> Result<()> {
         config_manager,
         &enabled_tools,
         http_config,
+        proxy_options,
         shutdown_token,
     )
     .await
@@ -164,7 +204,21 @@ async fn main() -> Result<()> {
     // Serve stdio transport (thin client mode)
     server.serve_stdio().await?;
 
-    Ok(())
+    Ok(ExitCode::SUCCESS)
+}
+
+/// HTTP connection settings for category servers (CLI flags override the config file)
+fn http_connection_config(
+    cli: &Cli,
+    config_manager: &kodegen_config_manager::ConfigManager,
+) -> stdio::HttpConnectionConfig {
+    stdio::HttpConnectionConfig {
+        connection_timeout: cli.http_connection_timeout(config_manager),
+        max_retries: cli.http_max_retries(),
+        retry_backoff: cli.http_retry_backoff_duration(),
+        host: cli.effective_host().to_string(),
+        no_tls: cli.no_tls,
+    }
 }
 
 /// Wait for interrupt signal (cross-platform)
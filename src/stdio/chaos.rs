@@ -0,0 +1,224 @@
+//! Fault injection for resilience testing (cargo feature `chaos`)
+//!
+//! Started with `--chaos <file>`, the proxy delays, drops, or fails tool calls
+//! according to a JSON config before they reach the category servers, so agents
+//! built on kodegen can exercise their retry and error handling.
+//!
+//! ```json
+//! {
+//!   "rules": [
+//!     { "tool": "fs_read_file", "error_rate": 0.2, "error_message": "disk on fire" },
+//!     { "category": "git", "latency_ms": 500, "latency_jitter_ms": 1500 },
+//!     { "drop_rate": 0.05 }
+//!   ]
+//! }
+//! ```
+//!
+//! The first rule whose `tool` and `category` match (an omitted field matches
+//! anything) applies to a call. Latency is always added; afterwards the call is
+//! dropped with probability `drop_rate` (a transport error, as if the
+//! connection was lost), otherwise fails with probability `error_rate` (an
+//! `is_error` tool result).
+
+use anyhow::{Context, Result};
+use rand::Rng;
+use rmcp::ErrorData as McpError;
+use rmcp::model::{CallToolResult, Content};
+use serde::Deserialize;
+use std::path::Path;
+use std::time::Duration;
+
+/// Fault injection config file contents
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ChaosConfig {
+    /// Rules checked in order; the first match applies
+    #[serde(default)]
+    pub rules: Vec<ChaosRule>,
+}
+
+/// Faults to inject into matching tool calls
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ChaosRule {
+    /// Tool name to match (any tool when omitted)
+    pub tool: Option<String>,
+    /// Category name to match (any category when omitted)
+    pub category: Option<String>,
+    /// Fixed delay added before the call
+    #[serde(default)]
+    pub latency_ms: u64,
+    /// Extra random delay, uniformly distributed in 0..=latency_jitter_ms
+    #[serde(default)]
+    pub latency_jitter_ms: u64,
+    /// Probability (0.0-1.0) of dropping the call with a transport error
+    #[serde(default)]
+    pub drop_rate: f64,
+    /// Probability (0.0-1.0) of returning an error result
+    #[serde(default)]
+    pub error_rate: f64,
+    /// Message for injected error results
+    pub error_message: Option<String>,
+}
+
+/// Fault chosen for a single call
+#[derive(Debug, Clone, PartialEq)]
+pub enum Fault {
+    /// Fail with a transport error
+    Drop,
+    /// Return an `is_error` tool result with this message
+    Error(String),
+}
+
+/// What to do to a single call
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChaosPlan {
+    /// Delay before the call proceeds (or fails)
+    pub delay: Duration,
+    /// Fault replacing the real call, if any
+    pub fault: Option<Fault>,
+}
+
+impl ChaosRule {
+    fn matches(&self, tool: &str, category: &str) -> bool {
+        self.tool.as_deref().is_none_or(|t| t == tool)
+            && self.category.as_deref().is_none_or(|c| c == category)
+    }
+}
+
+impl ChaosConfig {
+    /// Load and validate a config file
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read chaos config {}", path.display()))?;
+
+        let config: Self = serde_json::from_str(&content)
+            .with_context(|| format!("Invalid chaos config {}", path.display()))?;
+
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Reject rates outside 0.0-1.0
+    fn validate(&self) -> Result<()> {
+        for (index, rule) in self.rules.iter().enumerate() {
+            for (name, rate) in [("drop_rate", rule.drop_rate), ("error_rate", rule.error_rate)] {
+                if !(0.0..=1.0).contains(&rate) {
+                    anyhow::bail!("Chaos rule {}: {} must be between 0.0 and 1.0, got {}", index + 1, name, rate);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Decide the delay and fault for a call
+    pub fn plan(&self, tool: &str, category: &str, rng: &mut impl Rng) -> ChaosPlan {
+        let Some(rule) = self.rules.iter().find(|rule| rule.matches(tool, category)) else {
+            return ChaosPlan::default();
+        };
+
+        let jitter = if rule.latency_jitter_ms > 0 {
+            rng.random_range(0..=rule.latency_jitter_ms)
+        } else {
+            0
+        };
+
+        let fault = if rule.drop_rate > 0.0 && rng.random_bool(rule.drop_rate) {
+            Some(Fault::Drop)
+        } else if rule.error_rate > 0.0 && rng.random_bool(rule.error_rate) {
+            Some(Fault::Error(
+                rule.error_message
+                    .clone()
+                    .unwrap_or_else(|| format!("Injected failure for tool '{}'", tool)),
+            ))
+        } else {
+            None
+        };
+
+        ChaosPlan {
+            delay: Duration::from_millis(rule.latency_ms + jitter),
+            fault,
+        }
+    }
+
+    /// Apply the planned faults to a call
+    ///
+    /// Sleeps for the planned delay, then returns the injected outcome, or None
+    /// when the call should proceed to the category server.
+    pub async fn inject(&self, tool: &str, category: &str) -> Option<Result<CallToolResult, McpError>> {
+        let plan = self.plan(tool, category, &mut rand::rng());
+
+        if !plan.delay.is_zero() {
+            log::debug!("Chaos: delaying '{}' by {:?}", tool, plan.delay);
+            tokio::time::sleep(plan.delay).await;
+        }
+
+        match plan.fault? {
+            Fault::Drop => {
+                log::debug!("Chaos: dropping '{}'", tool);
+                Some(Err(McpError::internal_error(
+                    format!("HTTP client error: connection to {} server dropped (chaos)", category),
+                    None,
+                )))
+            }
+            Fault::Error(message) => {
+                log::debug!("Chaos: failing '{}'", tool);
+                Some(Ok(CallToolResult::error(vec![Content::text(message)])))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(json: &str) -> ChaosConfig {
+        let config: ChaosConfig = serde_json::from_str(json).unwrap();
+        config.validate().unwrap();
+        config
+    }
+
+    #[test]
+    fn test_first_matching_rule_applies() {
+        let config = config(
+            r#"{ "rules": [
+                { "tool": "fs_read_file", "error_rate": 1.0, "error_message": "boom" },
+                { "category": "filesystem", "latency_ms": 250 }
+            ] }"#,
+        );
+        let mut rng = rand::rng();
+
+        let plan = config.plan("fs_read_file", "filesystem", &mut rng);
+        assert_eq!(plan.fault, Some(Fault::Error("boom".to_string())));
+        assert!(plan.delay.is_zero());
+
+        let plan = config.plan("fs_write_file", "filesystem", &mut rng);
+        assert_eq!(plan.fault, None);
+        assert_eq!(plan.delay, Duration::from_millis(250));
+
+        assert_eq!(config.plan("git_log", "git", &mut rng), ChaosPlan::default());
+    }
+
+    #[test]
+    fn test_drop_takes_precedence_over_error() {
+        let config = config(r#"{ "rules": [{ "drop_rate": 1.0, "error_rate": 1.0 }] }"#);
+        let plan = config.plan("git_log", "git", &mut rand::rng());
+        assert_eq!(plan.fault, Some(Fault::Drop));
+    }
+
+    #[test]
+    fn test_latency_jitter_is_bounded() {
+        let config = config(r#"{ "rules": [{ "latency_ms": 100, "latency_jitter_ms": 50 }] }"#);
+        let mut rng = rand::rng();
+
+        for _ in 0..20 {
+            let delay = config.plan("git_log", "git", &mut rng).delay;
+            assert!(delay >= Duration::from_millis(100) && delay <= Duration::from_millis(150));
+        }
+    }
+
+    #[test]
+    fn test_invalid_rate_is_rejected() {
+        let config: ChaosConfig = serde_json::from_str(r#"{ "rules": [{ "error_rate": 1.5 }] }"#).unwrap();
+        assert!(config.validate().is_err());
+    }
+}
@@ -1,9 +1,12 @@
 //! Tool routing metadata for stdio server proxy.
 //!
 //! Tool metadata is now discovered automatically via inventory::iter<ToolMetadata>
-//! from kodegen-mcp-schema. This module only contains infrastructure routing.
+//! from kodegen-mcp-schema. This module only contains infrastructure routing
+//! and the consistency checks between metadata, routing, and bundled toolsets.
 
 mod routing;
+mod verify;
 
 // Re-export routing infrastructure
 pub use routing::{get_routing_table, CATEGORY_PORTS};
+pub use verify::verify_metadata;
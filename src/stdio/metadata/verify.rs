@@ -0,0 +1,130 @@
+//! Consistency checks for tool metadata, routing, and bundled toolsets.
+//!
+//! Tool metadata comes from the ToolMetadata inventory, but routing depends on
+//! the port table in kodegen-config and the bundled toolsets list tools by
+//! name. When these disagree, tools silently disappear from list_tools or
+//! toolsets fail at startup. `verify_metadata()` reports every mismatch; it
+//! backs `kodegen dev verify-metadata` and the unit test below.
+
+use std::collections::{HashMap, HashSet};
+
+use kodegen_mcp_schema::ToolMetadata;
+
+use super::routing::CATEGORY_PORTS;
+use crate::cli::toolset::ToolsetConfig;
+
+/// Check tool metadata against the port table and bundled toolsets
+///
+/// Returns one message per problem found (empty when consistent).
+pub fn verify_metadata() -> Vec<String> {
+    let mut problems = Vec::new();
+
+    let categories: HashSet<&str> = CATEGORY_PORTS.iter().map(|(cat, _port)| cat.name).collect();
+
+    // Ports must be unique, or two categories would share one server
+    let mut ports: HashMap<u16, &str> = HashMap::new();
+    for &(category, port) in CATEGORY_PORTS {
+        if let Some(other) = ports.insert(port, category.name) {
+            problems.push(format!(
+                "Port {} is assigned to both '{}' and '{}'",
+                port, other, category.name
+            ));
+        }
+    }
+
+    let mut tool_names: HashSet<&str> = HashSet::new();
+    for tool in inventory::iter::<ToolMetadata>() {
+        if !tool_names.insert(tool.name) {
+            problems.push(format!("Tool '{}' is registered more than once", tool.name));
+        }
+
+        if !categories.contains(tool.category.name) {
+            problems.push(format!(
+                "Tool '{}' belongs to category '{}', which has no port assignment (it can never be routed)",
+                tool.name, tool.category.name
+            ));
+        }
+
+        if let Some(problem) = description_problem(tool.description) {
+            problems.push(format!("Tool '{}' {}", tool.name, problem));
+        }
+
+        // list_tools falls back to an empty schema for anything but an object schema
+        match (tool.args_schema)() {
+            serde_json::Value::Object(schema) => {
+                if let Some(kind) = schema.get("type")
+                    && kind != "object"
+                {
+                    problems.push(format!(
+                        "Tool '{}' has an args schema of type {} (expected \"object\")",
+                        tool.name, kind
+                    ));
+                }
+            }
+            other => problems.push(format!(
+                "Tool '{}' args schema is not a JSON object: {}",
+                tool.name, other
+            )),
+        }
+    }
+
+    for toolset in crate::embedded::list_toolsets() {
+        let path = format!("toolset/{}.json", toolset);
+        let Some(content) = crate::embedded::get_file(&path) else {
+            continue;
+        };
+
+        match serde_json::from_str::<ToolsetConfig>(content) {
+            Ok(config) => {
+                for name in &config.tools {
+                    if !tool_names.contains(name.as_str()) {
+                        problems.push(format!(
+                            "Bundled toolset '{}' references unknown tool '{}'",
+                            toolset, name
+                        ));
+                    }
+                }
+            }
+            Err(e) => problems.push(format!("Bundled toolset '{}' is invalid: {}", toolset, e)),
+        }
+    }
+
+    problems
+}
+
+/// Check a tool description, returning what is wrong with it
+///
+/// Empty descriptions and scaffolding placeholders ("TODO: ...") leave the
+/// model without any idea of when to use the tool.
+fn description_problem(description: &str) -> Option<&'static str> {
+    let description = description.trim();
+
+    if description.is_empty() {
+        Some("has an empty description")
+    } else if description.starts_with("TODO:") {
+        Some("has a placeholder description (starts with TODO:)")
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metadata_is_consistent() {
+        let problems = verify_metadata();
+        assert!(problems.is_empty(), "metadata drift:\n{}", problems.join("\n"));
+    }
+
+    #[test]
+    fn test_description_problem() {
+        assert_eq!(description_problem("Read a file from disk"), None);
+        assert!(description_problem("   ").is_some());
+        assert!(description_problem("TODO: describe what git_blame_line does").is_some());
+        assert_eq!(description_problem("Todoist task sync"), None);
+        assert_eq!(description_problem("Todo list manager"), None);
+        assert_eq!(description_problem("TODOS are tracked in the issue tracker"), None);
+    }
+}
@@ -1,6 +1,11 @@
 // packages/server/src/stdio/mod.rs
+#[cfg(feature = "chaos")]
+pub mod chaos;
 pub mod metadata;
+pub mod replay;
 pub mod server;
 pub mod session_mapper;
+pub mod tool_groups;
+pub mod usage;
 
-pub use server::{HttpConnectionConfig, StdioProxyServer};
+pub use server::{HttpConnectionConfig, ProxyOptions, StdioProxyServer};
@@ -0,0 +1,287 @@
+//! Record and replay of tool calls for deterministic agent runs
+//!
+//! `--record <file>` appends every tool call the client makes (tool name,
+//! arguments, and the result or error it received) to a JSONL file.
+//! `--replay <file>` serves those recorded results instead of contacting the
+//! category servers, in order, and fails the first call that diverges from the
+//! recording (different tool or arguments). This makes agent workflows built on
+//! kodegen repeatable in regression tests.
+//!
+//! Calls are matched strictly in sequence, so replay assumes the client issues
+//! tool calls one at a time, as recorded.
+
+use anyhow::{Context, Result};
+use rmcp::ErrorData as McpError;
+use rmcp::model::{CallToolResult, JsonObject};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+
+/// One tool call as seen by the client
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedCall {
+    /// Tool name the client called
+    pub tool: String,
+    /// Arguments the client sent (before session ID mapping)
+    #[serde(default)]
+    pub arguments: JsonObject,
+    /// Result returned to the client
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<CallToolResult>,
+    /// Protocol error returned to the client
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<McpError>,
+}
+
+impl RecordedCall {
+    /// Capture a completed tool call
+    pub fn new(tool: &str, arguments: JsonObject, outcome: &Result<CallToolResult, McpError>) -> Self {
+        Self {
+            tool: tool.to_string(),
+            arguments,
+            result: outcome.as_ref().ok().cloned(),
+            error: outcome.as_ref().err().cloned(),
+        }
+    }
+
+    /// The outcome to hand back to the client during replay
+    fn outcome(&self) -> Result<CallToolResult, McpError> {
+        match (&self.result, &self.error) {
+            (Some(result), _) => Ok(result.clone()),
+            (None, Some(error)) => Err(error.clone()),
+            (None, None) => Err(McpError::internal_error(
+                format!("Recorded call to '{}' has neither result nor error", self.tool),
+                None,
+            )),
+        }
+    }
+}
+
+/// Appends tool calls to a JSONL recording
+pub struct Recorder {
+    path: PathBuf,
+    file: tokio::sync::Mutex<tokio::fs::File>,
+}
+
+impl Recorder {
+    /// Create (or truncate) the recording file
+    pub async fn create(path: &Path) -> Result<Self> {
+        let file = tokio::fs::File::create(path)
+            .await
+            .with_context(|| format!("Failed to create recording {}", path.display()))?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            file: tokio::sync::Mutex::new(file),
+        })
+    }
+
+    /// Append a call to the recording (failures are logged, never surfaced to the client)
+    pub async fn record(&self, call: &RecordedCall) {
+        let mut line = match serde_json::to_vec(call) {
+            Ok(line) => line,
+            Err(e) => {
+                log::warn!("Failed to serialize recorded call to '{}': {}", call.tool, e);
+                return;
+            }
+        };
+        line.push(b'\n');
+
+        let mut file = self.file.lock().await;
+        if let Err(e) = async {
+            file.write_all(&line).await?;
+            file.flush().await
+        }
+        .await
+        {
+            log::warn!("Failed to write recording {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+/// Replay cursor
+#[derive(Debug, Default)]
+struct ReplayState {
+    /// Index of the next expected call
+    next: usize,
+    /// Message describing the first divergence, once one occurred
+    diverged: Option<String>,
+}
+
+/// Serves recorded results in order and detects divergence
+pub struct Replayer {
+    calls: Vec<RecordedCall>,
+    state: std::sync::Mutex<ReplayState>,
+}
+
+impl Replayer {
+    /// Load a JSONL recording
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read recording {}", path.display()))?;
+
+        let calls = Self::parse(&content)
+            .with_context(|| format!("Invalid recording {}", path.display()))?;
+
+        Ok(Self::new(calls))
+    }
+
+    /// Create a replayer from recorded calls
+    pub fn new(calls: Vec<RecordedCall>) -> Self {
+        Self {
+            calls,
+            state: std::sync::Mutex::new(ReplayState::default()),
+        }
+    }
+
+    /// Parse JSONL content, skipping blank lines
+    fn parse(content: &str) -> Result<Vec<RecordedCall>> {
+        content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                serde_json::from_str(line).with_context(|| format!("line {}", index + 1))
+            })
+            .collect()
+    }
+
+    /// Number of recorded calls
+    pub fn len(&self) -> usize {
+        self.calls.len()
+    }
+
+    /// Whether the recording contains no calls
+    pub fn is_empty(&self) -> bool {
+        self.calls.is_empty()
+    }
+
+    /// Number of recorded calls not yet replayed
+    pub fn remaining(&self) -> usize {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        self.calls.len().saturating_sub(state.next)
+    }
+
+    /// Serve the next recorded outcome, or fail if the call diverges
+    ///
+    /// After the first divergence every later call fails too, since the run
+    /// no longer follows the recording.
+    pub fn next(&self, tool: &str, arguments: Option<&JsonObject>) -> Result<CallToolResult, McpError> {
+        let empty = JsonObject::new();
+        let arguments = arguments.unwrap_or(&empty);
+
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+
+        if let Some(ref diverged) = state.diverged {
+            return Err(McpError::internal_error(
+                format!("Replay already diverged: {}", diverged),
+                None,
+            ));
+        }
+
+        let index = state.next;
+        let Some(expected) = self.calls.get(index) else {
+            let message = format!(
+                "call {} to '{}' is past the end of the recording ({} calls)",
+                index + 1,
+                tool,
+                self.calls.len()
+            );
+            state.diverged = Some(message.clone());
+            return Err(McpError::internal_error(
+                format!("Replay diverged: {}", message),
+                Some(json!({ "index": index, "actual": { "tool": tool, "arguments": arguments } })),
+            ));
+        };
+
+        if expected.tool != tool || &expected.arguments != arguments {
+            let message = format!(
+                "call {} expected '{}' but got '{}'{}",
+                index + 1,
+                expected.tool,
+                tool,
+                if expected.tool == tool { " with different arguments" } else { "" }
+            );
+            state.diverged = Some(message.clone());
+            return Err(McpError::internal_error(
+                format!("Replay diverged: {}", message),
+                Some(json!({
+                    "index": index,
+                    "expected": { "tool": expected.tool, "arguments": expected.arguments },
+                    "actual": { "tool": tool, "arguments": arguments },
+                })),
+            ));
+        }
+
+        state.next += 1;
+        expected.outcome()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::Content;
+
+    fn recorded(tool: &str, arguments: JsonObject, text: &str) -> RecordedCall {
+        RecordedCall::new(tool, arguments, &Ok(CallToolResult::success(vec![Content::text(text)])))
+    }
+
+    #[test]
+    fn test_recording_round_trip() {
+        let calls = vec![
+            recorded("fs_read_file", json!({ "path": "a.txt" }).as_object().unwrap().clone(), "hello"),
+            RecordedCall::new(
+                "git_log",
+                JsonObject::new(),
+                &Err(McpError::invalid_params("not a repository", None)),
+            ),
+        ];
+
+        let content: String = calls
+            .iter()
+            .map(|call| serde_json::to_string(call).unwrap() + "\n")
+            .collect();
+
+        assert_eq!(Replayer::parse(&content).unwrap(), calls);
+    }
+
+    #[test]
+    fn test_replay_in_order() {
+        let replayer = Replayer::new(vec![
+            recorded("fs_read_file", json!({ "path": "a.txt" }).as_object().unwrap().clone(), "a"),
+            recorded("fs_read_file", json!({ "path": "b.txt" }).as_object().unwrap().clone(), "b"),
+        ]);
+
+        let first = replayer.next("fs_read_file", json!({ "path": "a.txt" }).as_object()).unwrap();
+        assert_eq!(first.content, vec![Content::text("a")]);
+        assert_eq!(replayer.remaining(), 1);
+
+        replayer.next("fs_read_file", json!({ "path": "b.txt" }).as_object()).unwrap();
+        assert_eq!(replayer.remaining(), 0);
+    }
+
+    #[test]
+    fn test_divergence_is_sticky() {
+        let replayer = Replayer::new(vec![
+            recorded("fs_read_file", json!({ "path": "a.txt" }).as_object().unwrap().clone(), "a"),
+            recorded("git_log", JsonObject::new(), "log"),
+        ]);
+
+        let err = replayer.next("fs_read_file", json!({ "path": "other.txt" }).as_object()).unwrap_err();
+        assert!(err.message.contains("different arguments"));
+
+        // The recorded next call no longer matches once the run has diverged
+        assert!(replayer.next("fs_read_file", json!({ "path": "a.txt" }).as_object()).is_err());
+    }
+
+    #[test]
+    fn test_calls_past_end_diverge() {
+        let replayer = Replayer::new(vec![recorded("git_log", JsonObject::new(), "log")]);
+
+        replayer.next("git_log", None).unwrap();
+        let err = replayer.next("git_log", None).unwrap_err();
+        assert!(err.message.contains("past the end"));
+    }
+}
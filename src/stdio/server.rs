@@ -7,11 +7,11 @@ use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use rmcp::{
     ErrorData as McpError, RoleServer, ServerHandler, ServiceExt,
     model::{
-        CallToolRequestParam, CallToolResult, GetPromptRequestParam, GetPromptResult,
-        Implementation, InitializeRequestParam, InitializeResult, ListPromptsResult,
-        ListResourceTemplatesResult, ListResourcesResult, ListToolsResult, PaginatedRequestParam,
-        ProtocolVersion, ReadResourceRequestParam, ReadResourceResult, ServerCapabilities,
-        ServerInfo, Tool,
+        CallToolRequestParam, CallToolResult, ExperimentalCapabilities, GetPromptRequestParam,
+        GetPromptResult, Implementation, InitializeRequestParam, InitializeResult, JsonObject,
+        ListPromptsResult, ListResourceTemplatesResult, ListResourcesResult, ListToolsResult,
+        PaginatedRequestParam, ProtocolVersion, ReadResourceRequestParam, ReadResourceResult,
+        ServerCapabilities, ServerInfo, Tool,
     },
     service::{QuitReason, RequestContext},
     transport::stdio,
@@ -23,9 +23,14 @@ use std::sync::Arc;
 use std::time::Duration;
 use tokio_util::sync::CancellationToken;
 
+#[cfg(feature = "chaos")]
+use super::chaos::ChaosConfig;
 use super::metadata::{get_routing_table, CATEGORY_PORTS};
 use kodegen_mcp_schema::ToolMetadata;
+use super::replay::{RecordedCall, Recorder, Replayer};
 use super::session_mapper::SessionMapper;
+use super::tool_groups::{self, GatewayRequest};
+use super::usage::{workspace_hash, UsageStats};
 use uuid::Uuid;
 
 
@@ -56,6 +61,22 @@ impl Default for HttpConnectionConfig {
     }
 }
 
+/// Optional proxy behaviors enabled from the command line
+#[derive(Debug, Clone, Default)]
+pub struct ProxyOptions {
+    /// Order list_tools results by per-workspace usage statistics
+    pub order_tools_by_usage: bool,
+    /// Expose one gateway tool per category instead of every tool
+    pub tool_groups: bool,
+    /// Record every tool call and its result to this JSONL file
+    pub record: Option<PathBuf>,
+    /// Serve tool results from this recording instead of the category servers
+    pub replay: Option<PathBuf>,
+    /// Inject latency, dropped connections, and errors per this config file
+    #[cfg(feature = "chaos")]
+    pub chaos: Option<PathBuf>,
+}
+
 /// Find git repository root by walking up from start directory
 fn find_git_root(start: &Path) -> Option<PathBuf> {
     let mut current = start.to_path_buf();
@@ -69,6 +90,45 @@ fn find_git_root(start: &Path) -> Option<PathBuf> {
     }
 }
 
+/// Build the base URL (scheme, host and port) of a category HTTP server
+///
+/// IPv6 literals are bracketed (`::1` -> `[::1]`) as URLs require; hostnames
+/// resolve to whichever address families the system resolver returns.
+pub(crate) fn category_base_url(http_config: &HttpConnectionConfig, port: u16) -> String {
+    let protocol = if http_config.no_tls { "http" } else { "https" };
+    let host = http_config.host.as_str();
+
+    if host.parse::<std::net::Ipv6Addr>().is_ok() {
+        format!("{}://[{}]:{}", protocol, host, port)
+    } else {
+        format!("{}://{}:{}", protocol, host, port)
+    }
+}
+
+/// Validate a category server host given with `--host`
+///
+/// Rejects IPv6 addresses with a zone ID (`fe80::1%eth0`): the HTTP client's
+/// URL parser cannot represent them, so the connection could never be made.
+pub(crate) fn validate_host(host: &str) -> Result<String, String> {
+    let bracketed = host.strip_prefix('[').and_then(|inner| inner.strip_suffix(']'));
+
+    if let Some((addr, zone)) = bracketed.unwrap_or(host).split_once('%')
+        && addr.parse::<std::net::Ipv6Addr>().is_ok()
+    {
+        // In URL form the '%' delimiter is itself percent-encoded as "%25"
+        let zone = match bracketed {
+            Some(_) => zone.strip_prefix("25").unwrap_or(zone),
+            None => zone,
+        };
+        return Err(format!(
+            "IPv6 zone IDs are not supported (zone '{}' in '{}'); use a global, unique local, or loopback address",
+            zone, host
+        ));
+    }
+
+    Ok(host.to_string())
+}
+
 /// Connect to HTTP server with exponential backoff retry
 ///
 /// Attempts connection up to `max_attempts` times with exponential backoff.
@@ -85,7 +145,7 @@ fn find_git_root(start: &Path) -> Option<PathBuf> {
 /// # Returns
 /// * `Ok((client, connection))` - Successfully connected client and connection tuple
 /// * `Err` - Connection failed (timeout, cancellation, or connection error)
-async fn connect_with_retry(
+pub(crate) async fn connect_with_retry(
     url: &str,
     connection_id: &str,
     max_attempts: u32,
@@ -231,6 +291,29 @@ pub struct StdioProxyServer {
     /// Unique connection ID for this stdio server instance
     /// Generated once per stdio connection to isolate sessions
     connection_id: String,
+
+    /// Namespace for shared session names (hash of the workspace git root)
+    shared_session_scope: String,
+
+    /// Categories that were requested but failed to connect at startup
+    /// Surfaced to clients through the experimental capabilities map
+    offline_categories: Vec<String>,
+
+    /// Per-workspace tool usage (Some only with --order-tools-by-usage)
+    usage_stats: Option<UsageStats>,
+
+    /// Expose category gateway tools instead of individual tools (--tool-groups)
+    tool_groups: bool,
+
+    /// Tool call recorder (Some only with --record)
+    recorder: Option<Recorder>,
+
+    /// Recorded tool results to serve (Some only with --replay)
+    replayer: Option<Replayer>,
+
+    /// Fault injection rules (Some only with --chaos)
+    #[cfg(feature = "chaos")]
+    chaos: Option<ChaosConfig>,
 }
 
 impl StdioProxyServer {
@@ -240,11 +323,13 @@ impl StdioProxyServer {
     /// * `config_manager` - Configuration manager
     /// * `enabled_tools` - Individual tool names to enable (from CLI --tool/--tools/--toolset)
     /// * `http_config` - HTTP connection configuration (retry, timeout, etc.)
+    /// * `options` - Optional proxy behaviors (tool ordering, tool groups, record/replay, etc.)
     /// * `shutdown_token` - Cancellation token for graceful shutdown during initialization
     pub async fn new(
         config_manager: kodegen_config_manager::ConfigManager,
         enabled_tools: &Option<std::collections::HashSet<String>>,
         http_config: HttpConnectionConfig,
+        options: ProxyOptions,
         shutdown_token: CancellationToken,
     ) -> Result<Self> {
         // Generate connection ID for this stdio server instance
@@ -254,6 +339,19 @@ impl StdioProxyServer {
         // Build routing table from static metadata
         let routing_table = get_routing_table().clone();
 
+        let replayer = options.replay.as_deref().map(Replayer::load).transpose()?;
+        let recorder = match options.record {
+            Some(ref path) => Some(Recorder::create(path).await?),
+            None => None,
+        };
+
+        #[cfg(feature = "chaos")]
+        let chaos = options.chaos.as_deref().map(ChaosConfig::load).transpose()?;
+        #[cfg(feature = "chaos")]
+        if let Some(ref chaos) = chaos {
+            log::warn!("Chaos mode enabled: {} fault injection rule(s) active", chaos.rules.len());
+        }
+
         // Determine which categories need HTTP connections based on enabled TOOLS
         let mut categories_to_connect: std::collections::HashSet<&str> = std::collections::HashSet::new();
         
@@ -271,7 +369,13 @@ impl StdioProxyServer {
             }
         }
         
-        let categories_vec: Vec<&str> = categories_to_connect.iter().copied().collect();
+        // Replayed results come from the recording, so no category servers are needed
+        let categories_vec: Vec<&str> = if let Some(ref replayer) = replayer {
+            log::info!("Replaying {} recorded tool call(s)", replayer.len());
+            Vec::new()
+        } else {
+            categories_to_connect.iter().copied().collect()
+        };
 
         log::info!(
             "Connecting to {} category servers: {}",
@@ -282,6 +386,7 @@ impl StdioProxyServer {
         // Connect to each category server
         let mut category_clients = HashMap::new();
         let mut category_connections = Vec::new();
+        let mut offline_categories = Vec::new();
         let port_map: HashMap<&str, u16> = CATEGORY_PORTS
             .iter()
             .map(|(cat, port)| (cat.name, *port))
@@ -292,9 +397,8 @@ impl StdioProxyServer {
                 anyhow::anyhow!("No port assignment for category: {}", category)
             })?;
 
-            let protocol = if http_config.no_tls { "http" } else { "https" };
-            let url = format!("{}://{}:{}/mcp", protocol, http_config.host, port);
-            
+            let url = format!("{}/mcp", category_base_url(&http_config, port));
+
             log::debug!("Connecting to {category} server at {url}");
 
             match connect_with_retry(
@@ -318,11 +422,12 @@ impl StdioProxyServer {
                     log::warn!(
                         "Failed to connect to {category} server (port {port}): {e}. Tools in this category will be unavailable."
                     );
+                    offline_categories.push(category.to_string());
                 }
             }
         }
 
-        if category_clients.is_empty() {
+        if category_clients.is_empty() && replayer.is_none() {
             return Err(anyhow::anyhow!(
                 "Failed to connect to any category servers. No tools available."
             ));
@@ -331,6 +436,12 @@ impl StdioProxyServer {
         // Store enabled_tools for filtering during list_tools
         let enabled_tools_set = enabled_tools.clone();
 
+        // Usage statistics and shared session names are scoped to the workspace (git root, else cwd)
+        let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+        let workspace = find_git_root(&cwd).unwrap_or(cwd);
+        let usage_stats = options.order_tools_by_usage.then(|| UsageStats::load(&workspace));
+        let shared_session_scope = format!("{:016x}", workspace_hash(&workspace));
+
         log::info!(
             "Stdio proxy server initialized with {} category connections",
             category_clients.len()
@@ -346,6 +457,14 @@ impl StdioProxyServer {
             config_manager,
             session_mapper: SessionMapper::new(),
             connection_id,
+            shared_session_scope,
+            offline_categories,
+            usage_stats,
+            tool_groups: options.tool_groups,
+            recorder,
+            replayer,
+            #[cfg(feature = "chaos")]
+            chaos,
         })
     }
 
@@ -372,8 +491,7 @@ impl StdioProxyServer {
             anyhow::anyhow!("No port assignment for category: {}", category)
         })?;
 
-        let protocol = if self.http_config.no_tls { "http" } else { "https" };
-        let url = format!("{}://{}:{}/mcp", protocol, self.http_config.host, port);
+        let url = format!("{}/mcp", category_base_url(&self.http_config, port));
 
         log::info!("Reconnecting to {} server at {}", category, url);
 
@@ -406,6 +524,59 @@ impl StdioProxyServer {
         Ok(new_client_extended)
     }
 
+    /// Categories currently able to serve tool calls, with the status to report
+    ///
+    /// Replayed categories are all served from the recording. get_info() is
+    /// synchronous, so connected categories are skipped if a reconnect holds the lock.
+    fn serving_categories(&self) -> (Vec<String>, &'static str) {
+        if self.replayer.is_some() {
+            let categories = CATEGORY_PORTS.iter().map(|(cat, _port)| cat.name.to_string()).collect();
+            return (categories, "replay");
+        }
+
+        let categories: Vec<String> = self
+            .category_clients
+            .try_read()
+            .map(|clients| clients.keys().cloned().collect())
+            .unwrap_or_default();
+        (categories, "online")
+    }
+
+    /// Requested categories that are not currently connected
+    fn current_offline_categories(&self, serving: &[String]) -> Vec<String> {
+        self.offline_categories
+            .iter()
+            .filter(|category| !serving.contains(category))
+            .cloned()
+            .collect()
+    }
+
+    /// Build the kodegen capabilities map advertised in ServerInfo
+    ///
+    /// Reports which categories are online (or replayed) or offline, and how
+    /// many tools each contributes after --tool/--tools/--toolset filtering,
+    /// so clients can tell which integrations are live before calling tools.
+    fn capabilities_map(&self) -> serde_json::Map<String, serde_json::Value> {
+        let (serving, status) = self.serving_categories();
+        let offline = self.current_offline_categories(&serving);
+
+        let categories = category_status_map(&serving, &offline, status, |category| {
+            self.category_tools(category).len()
+        });
+
+        let mut capabilities = serde_json::Map::new();
+        capabilities.insert("categories".to_string(), serde_json::Value::Object(categories));
+        capabilities.insert(
+            "tool_filter".to_string(),
+            serde_json::Value::Bool(self.enabled_tools.is_some()),
+        );
+        capabilities.insert(
+            "tool_groups".to_string(),
+            serde_json::Value::Bool(self.tool_groups),
+        );
+        capabilities
+    }
+
     /// Serve the stdio server
     pub async fn serve_stdio(self) -> Result<()> {
         log::info!("Starting stdio server (thin client mode with static metadata)");
@@ -445,16 +616,32 @@ impl StdioProxyServer {
 
 impl ServerHandler for StdioProxyServer {
     fn get_info(&self) -> ServerInfo {
+        let capabilities = self.capabilities_map();
+
+        let (serving, _status) = self.serving_categories();
+        let offline = self.current_offline_categories(&serving);
+
+        let mut instructions =
+            "KODEGEN Stdio Server (thin client) - MCP tools via stdio transport using static metadata".to_string();
+        if !offline.is_empty() {
+            instructions.push_str(&format!(
+                ". Offline categories (tools unavailable): {}",
+                offline.join(", ")
+            ));
+        }
+
+        let mut experimental = ExperimentalCapabilities::new();
+        experimental.insert("kodegen".to_string(), capabilities);
+
         ServerInfo {
             protocol_version: ProtocolVersion::V_2024_11_05,
             capabilities: ServerCapabilities::builder()
+                .enable_experimental_with(experimental)
                 .enable_tools()
                 .enable_prompts()
                 .build(),
             server_info: Implementation::from_build_env(),
-            instructions: Some(
-                "KODEGEN Stdio Server (thin client) - MCP tools via stdio transport using static metadata".to_string(),
-            ),
+            instructions: Some(instructions),
         }
     }
 
@@ -463,142 +650,39 @@ impl ServerHandler for StdioProxyServer {
         request: CallToolRequestParam,
         _context: RequestContext<RoleServer>,
     ) -> Result<CallToolResult, McpError> {
-        let tool_name = request.name.clone();
+        if let Some(ref replayer) = self.replayer {
+            return replayer.next(&request.name, request.arguments.as_ref());
+        }
 
-        // Check if tool is enabled
-        if let Some(ref enabled) = self.enabled_tools
-            && !enabled.contains(&*tool_name) {
-                return Err(McpError::invalid_params(
-                    format!("Tool '{}' is not enabled", tool_name),
-                    None,
-                ));
-            }
+        // Record what the client sent, before session ID mapping or gateway dispatch
+        let recorded_arguments = self
+            .recorder
+            .as_ref()
+            .map(|_| request.arguments.clone().unwrap_or_default());
 
-        // Route to appropriate category server
-        let (category, _port) = self.routing_table.get(&*tool_name).ok_or_else(|| {
-            McpError::invalid_params(format!("Unknown tool: {}", tool_name), None)
-        })?;
+        let gateway = tool_groups::gateway_category(&request.name).filter(|category| {
+            self.tool_groups && CATEGORY_PORTS.iter().any(|(cat, _port)| cat.name == *category)
+        });
 
-        let clients = self.category_clients.read().await;
-        let client = clients.get(*category).cloned().ok_or_else(|| {
-            McpError::internal_error(
+        let result = match gateway {
+            Some(category) if self.category_clients.read().await.contains_key(category) => {
+                self.call_gateway(category, request.arguments).await
+            }
+            Some(category) => Err(McpError::internal_error(
                 format!(
-                    "Category server '{}' not connected (tool: {})",
-                    category, tool_name
+                    "Category '{}' is offline: its server is not connected, so {} is unavailable",
+                    category, request.name
                 ),
                 None,
-            )
-        })?;
-        drop(clients);
-
-        log::debug!(
-            "Proxying tool call '{}' to category '{}' server",
-            tool_name,
-            category
-        );
-
-        // Convert arguments to JSON value
-        let mut args = match request.arguments {
-            Some(map) => serde_json::Value::Object(map),
-            None => serde_json::Value::Object(serde_json::Map::new()),
+            )),
+            None => self.forward_tool_call(&request.name, request.arguments).await,
         };
 
-        // Handle session ID mapping for tools that use session_id
-        // This isolates sessions from different stdio connections when proxying to HTTP servers
-        if let serde_json::Value::Object(ref mut map) = args
-            && let Some(client_session_id) = map.get("session_id").and_then(|v| v.as_str())
-        {
-            // Map client's session_id to a unique server session_id for this connection
-            let server_session_id = self.session_mapper.map_session_id(
-                &self.connection_id,
-                client_session_id,
-            );
-
-            log::debug!(
-                "Mapped session_id for tool '{}': client='{}' -> server='{}'",
-                tool_name,
-                client_session_id,
-                server_session_id
-            );
-
-            // Replace session_id in args with the mapped server session_id
-            map.insert(
-                "session_id".to_string(),
-                serde_json::Value::String(server_session_id),
-            );
+        if let (Some(recorder), Some(arguments)) = (&self.recorder, recorded_arguments) {
+            recorder.record(&RecordedCall::new(&request.name, arguments, &result)).await;
         }
 
-        // Call tool via category HTTP client
-        let mut result = client.call_tool(&tool_name, args.clone()).await;
-
-        // Handle session expiry with automatic reconnection and retry
-        if let Err(ref e) = result {
-            let error_str: String = format!("{:?}", e);
-            
-            // Detect 401/Unauthorized errors (session expired)
-            if error_str.contains("401") || error_str.contains("Unauthorized") {
-                log::warn!(
-                    "Session expired for category '{}' (tool: {}). Attempting reconnection...",
-                    category,
-                    tool_name
-                );
-
-                // Attempt to reconnect to the category server
-                match self.reconnect_category(category).await {
-                    Ok(new_client) => {
-                        log::info!(
-                            "Reconnection successful for category '{}'. Retrying tool call '{}'...",
-                            category,
-                            tool_name
-                        );
-
-                        // Retry the tool call with the new client
-                        result = new_client.call_tool(&tool_name, args).await;
-
-                        match &result {
-                            Ok(_) => {
-                                log::info!(
-                                    "Tool call '{}' succeeded after session recovery",
-                                    tool_name
-                                );
-                            }
-                            Err(retry_error) => {
-                                log::error!(
-                                    "Tool call '{}' failed after session recovery: {}",
-                                    tool_name,
-                                    retry_error
-                                );
-                            }
-                        }
-                    }
-                    Err(reconnect_error) => {
-                        log::error!(
-                            "Failed to reconnect to category '{}' server: {}",
-                            category,
-                            reconnect_error
-                        );
-                        // Keep the original error
-                    }
-                }
-            } else {
-                log::error!("HTTP proxy error for tool '{}': {}", tool_name, e);
-            }
-        }
-
-        // Convert ClientError to ErrorData, preserving MCP errors from upstream
-        result.map_err(|e| {
-            match e {
-                // Extract the MCP error if it's already wrapped in a ServiceError
-                kodegen_mcp_client::ClientError::ServiceError(
-                    rmcp::ServiceError::McpError(mcp_err)
-                ) => mcp_err,
-                // For other errors, wrap as internal error
-                other => McpError::internal_error(
-                    format!("HTTP client error: {}", other),
-                    None
-                ),
-            }
-        })
+        result
     }
 
     async fn list_tools(
@@ -606,12 +690,27 @@ impl ServerHandler for StdioProxyServer {
         _request: Option<PaginatedRequestParam>,
         _context: RequestContext<RoleServer>,
     ) -> Result<ListToolsResult, McpError> {
+        // Tool groups mode: one gateway tool per connected category
+        let categories = self.available_categories().await;
+
+        if self.tool_groups {
+            let tools: Vec<Tool> = categories
+                .iter()
+                .filter_map(|category| {
+                    let names: Vec<&str> =
+                        self.category_tools(category).iter().map(|tool| tool.name).collect();
+                    (!names.is_empty()).then(|| tool_groups::gateway_tool(category, &names))
+                })
+                .collect();
+
+            log::debug!("Serving {} category gateway tools", tools.len());
+
+            return Ok(ListToolsResult::with_all_items(tools));
+        }
+
         // Serve tool metadata from static metadata (no tool instantiation)
         let mut tools = Vec::new();
 
-        // Acquire read lock for category_clients
-        let clients = self.category_clients.read().await;
-
         for tool_meta in inventory::iter::<ToolMetadata> {
             // Filter by enabled_tools if set
             if let Some(ref enabled) = self.enabled_tools
@@ -620,7 +719,7 @@ impl ServerHandler for StdioProxyServer {
                 }
 
             // Only include tools whose category server is connected
-            if !clients.contains_key(tool_meta.category.name) {
+            if !categories.iter().any(|category| category == tool_meta.category.name) {
                 continue;
             }
 
@@ -642,7 +741,10 @@ impl ServerHandler for StdioProxyServer {
             });
         }
 
-        drop(clients);
+        // Most useful tools first, so clients with tool-count limits keep them
+        if let Some(ref usage) = self.usage_stats {
+            usage.sort_tools(&mut tools);
+        }
 
         log::debug!("Serving {} tools from static metadata", tools.len());
 
@@ -754,8 +856,246 @@ impl ServerHandler for StdioProxyServer {
     }
 }
 
+impl StdioProxyServer {
+    /// Categories whose tools are listed: connected ones, or all of them when replaying
+    async fn available_categories(&self) -> Vec<String> {
+        let mut categories: Vec<String> = if self.replayer.is_some() {
+            CATEGORY_PORTS.iter().map(|(cat, _port)| cat.name.to_string()).collect()
+        } else {
+            self.category_clients.read().await.keys().cloned().collect()
+        };
+        categories.sort_unstable();
+        categories
+    }
+
+    /// Forward a tool call to the category server that owns the tool
+    ///
+    /// Applies the --tool/--tools/--toolset filter, session ID mapping,
+    /// reconnection on session expiry, and usage tracking.
+    async fn forward_tool_call(
+        &self,
+        tool_name: &str,
+        arguments: Option<JsonObject>,
+    ) -> Result<CallToolResult, McpError> {
+        // Check if tool is enabled
+        if let Some(ref enabled) = self.enabled_tools
+            && !enabled.contains(tool_name) {
+                return Err(McpError::invalid_params(
+                    format!("Tool '{}' is not enabled", tool_name),
+                    None,
+                ));
+            }
+
+        // Route to appropriate category server
+        let (category, _port) = self.routing_table.get(tool_name).ok_or_else(|| {
+            McpError::invalid_params(format!("Unknown tool: {}", tool_name), None)
+        })?;
+
+        let clients = self.category_clients.read().await;
+        let client = clients.get(*category).cloned().ok_or_else(|| {
+            McpError::internal_error(
+                format!(
+                    "Category server '{}' not connected (tool: {})",
+                    category, tool_name
+                ),
+                None,
+            )
+        })?;
+        drop(clients);
+
+        log::debug!(
+            "Proxying tool call '{}' to category '{}' server",
+            tool_name,
+            category
+        );
+
+        // Convert arguments to JSON value
+        let mut args = match arguments {
+            Some(map) => serde_json::Value::Object(map),
+            None => serde_json::Value::Object(serde_json::Map::new()),
+        };
+
+        // Handle session ID mapping for tools that use session_id
+        // This isolates sessions from different stdio connections when proxying to HTTP servers
+        if let serde_json::Value::Object(ref mut map) = args {
+            self.session_mapper.map_arguments(
+                &self.connection_id,
+                &self.shared_session_scope,
+                tool_name,
+                map,
+            );
+        }
+
+        // Injected faults replace the real call (chaos testing builds only)
+        #[cfg(feature = "chaos")]
+        if let Some(ref chaos) = self.chaos
+            && let Some(outcome) = chaos.inject(tool_name, category).await
+        {
+            return outcome;
+        }
+
+        // Call tool via category HTTP client
+        let mut result = client.call_tool(tool_name, args.clone()).await;
+
+        // Handle session expiry with automatic reconnection and retry
+        if let Err(ref e) = result {
+            let error_str: String = format!("{:?}", e);
+            
+            // Detect 401/Unauthorized errors (session expired)
+            if error_str.contains("401") || error_str.contains("Unauthorized") {
+                log::warn!(
+                    "Session expired for category '{}' (tool: {}). Attempting reconnection...",
+                    category,
+                    tool_name
+                );
+
+                // Attempt to reconnect to the category server
+                match self.reconnect_category(category).await {
+                    Ok(new_client) => {
+                        log::info!(
+                            "Reconnection successful for category '{}'. Retrying tool call '{}'...",
+                            category,
+                            tool_name
+                        );
+
+                        // Retry the tool call with the new client
+                        result = new_client.call_tool(tool_name, args).await;
+
+                        match &result {
+                            Ok(_) => {
+                                log::info!(
+                                    "Tool call '{}' succeeded after session recovery",
+                                    tool_name
+                                );
+                            }
+                            Err(retry_error) => {
+                                log::error!(
+                                    "Tool call '{}' failed after session recovery: {}",
+                                    tool_name,
+                                    retry_error
+                                );
+                            }
+                        }
+                    }
+                    Err(reconnect_error) => {
+                        log::error!(
+                            "Failed to reconnect to category '{}' server: {}",
+                            category,
+                            reconnect_error
+                        );
+                        // Keep the original error
+                    }
+                }
+            } else {
+                log::error!("HTTP proxy error for tool '{}': {}", tool_name, e);
+            }
+        }
+
+        // Track usage for --order-tools-by-usage
+        if let Some(ref usage) = self.usage_stats {
+            let success = matches!(&result, Ok(r) if !r.is_error.unwrap_or(false));
+            usage.record(tool_name, success);
+        }
+
+        // Convert ClientError to ErrorData, preserving MCP errors from upstream
+        result.map_err(|e| {
+            match e {
+                // Extract the MCP error if it's already wrapped in a ServiceError
+                kodegen_mcp_client::ClientError::ServiceError(
+                    rmcp::ServiceError::McpError(mcp_err)
+                ) => mcp_err,
+                // For other errors, wrap as internal error
+                other => McpError::internal_error(
+                    format!("HTTP client error: {}", other),
+                    None
+                ),
+            }
+        })
+    }
+
+    /// Enabled tools in a category, in inventory order
+    fn category_tools(&self, category: &str) -> Vec<&'static ToolMetadata> {
+        inventory::iter::<ToolMetadata>()
+            .filter(|tool| tool.category.name == category)
+            .filter(|tool| {
+                self.enabled_tools
+                    .as_ref()
+                    .is_none_or(|enabled| enabled.contains(tool.name))
+            })
+            .collect()
+    }
+
+    /// Handle a call to a category gateway tool (--tool-groups mode)
+    async fn call_gateway(
+        &self,
+        category: &str,
+        arguments: Option<JsonObject>,
+    ) -> Result<CallToolResult, McpError> {
+        let sub_tools = self.category_tools(category);
+
+        let find_sub_tool = |name: &str| {
+            sub_tools.iter().copied().find(|tool| tool.name == name).ok_or_else(|| {
+                let available: Vec<&str> = sub_tools.iter().map(|tool| tool.name).collect();
+                McpError::invalid_params(
+                    format!(
+                        "Unknown {} tool: {} (available: {})",
+                        category,
+                        name,
+                        available.join(", ")
+                    ),
+                    None,
+                )
+            })
+        };
+
+        match GatewayRequest::parse(arguments)? {
+            GatewayRequest::List => {
+                let tools: Vec<serde_json::Value> = sub_tools
+                    .iter()
+                    .map(|tool| json!({ "name": tool.name, "description": tool.description }))
+                    .collect();
+
+                Ok(CallToolResult::structured(json!({
+                    "category": category,
+                    "tools": tools,
+                })))
+            }
+            GatewayRequest::Describe(name) => {
+                let tool = find_sub_tool(&name)?;
+
+                Ok(CallToolResult::structured(json!({
+                    "name": tool.name,
+                    "description": tool.description,
+                    "input_schema": (tool.args_schema)(),
+                })))
+            }
+            GatewayRequest::Call { tool, args } => {
+                let tool = find_sub_tool(&tool)?;
+
+                log::debug!(
+                    "Dispatching gateway call '{}' to '{}'",
+                    tool_groups::gateway_name(category),
+                    tool.name
+                );
+
+                self.forward_tool_call(tool.name, Some(args)).await
+            }
+        }
+    }
+}
+
 impl Drop for StdioProxyServer {
     fn drop(&mut self) {
+        if let Some(ref replayer) = self.replayer
+            && replayer.remaining() > 0
+        {
+            log::warn!(
+                "Replay ended with {} of {} recorded tool call(s) not replayed",
+                replayer.remaining(),
+                replayer.len()
+            );
+        }
+
         // Clean up all session mappings for this connection
         //
         // This fires when the last Arc<StdioProxyServer> reference is dropped
@@ -799,6 +1139,32 @@ impl Drop for StdioProxyServer {
     }
 }
 
+/// Build the per-category status section of the capabilities map
+///
+/// Serving categories report `status` and their enabled tool count; offline
+/// categories report zero tools.
+fn category_status_map(
+    serving: &[String],
+    offline: &[String],
+    status: &str,
+    tool_count: impl Fn(&str) -> usize,
+) -> serde_json::Map<String, serde_json::Value> {
+    let mut categories = serde_json::Map::new();
+
+    for category in serving {
+        categories.insert(
+            category.clone(),
+            json!({ "status": status, "tools": tool_count(category) }),
+        );
+    }
+
+    for category in offline {
+        categories.insert(category.clone(), json!({ "status": "offline", "tools": 0 }));
+    }
+
+    categories
+}
+
 /// Helper struct for async notification in Drop
 struct StdioProxyServerClone {
     category_clients: Arc<tokio::sync::RwLock<HashMap<String, kodegen_mcp_client::KodegenClient>>>,
@@ -832,50 +1198,7 @@ async fn notify_backends_helper(server: &StdioProxyServerClone) {
         let port = *port_map.get(category.as_str()).unwrap_or(&0);
 
         let task = tokio::spawn(async move {
-            let protocol = if http_config.no_tls { "http" } else { "https" };
-            let url = format!(
-                "{}://{}:{}/mcp/connection/{}",
-                protocol, http_config.host, port, connection_id
-            );
-
-            log::debug!(
-                "Notifying {} server of connection {} drop",
-                category,
-                connection_id
-            );
-
-            // Send DELETE request with 5-second timeout
-            let client = reqwest::Client::builder()
-                .timeout(Duration::from_secs(5))
-                .build()
-                .unwrap();
-
-            match client.delete(&url).send().await {
-                Ok(response) => {
-                    if response.status().is_success() || response.status() == 204 {
-                        log::debug!(
-                            "{} server acknowledged connection {} cleanup",
-                            category,
-                            connection_id
-                        );
-                    } else {
-                        log::warn!(
-                            "{} server returned {} for connection {} cleanup",
-                            category,
-                            response.status(),
-                            connection_id
-                        );
-                    }
-                }
-                Err(e) => {
-                    log::warn!(
-                        "Failed to notify {} server of connection {} drop: {}",
-                        category,
-                        connection_id,
-                        e
-                    );
-                }
-            }
+            notify_connection_drop(&http_config, &category, port, &connection_id).await;
         });
 
         tasks.push(task);
@@ -893,3 +1216,120 @@ async fn notify_backends_helper(server: &StdioProxyServerClone) {
         start.elapsed()
     );
 }
+
+/// Tell a category server that a connection is gone
+///
+/// Sends `DELETE {base}/mcp/connection/{id}` so the server releases
+/// connection-scoped state (terminals, browser sessions, mapped sessions).
+/// Failures are logged, never returned.
+pub(crate) async fn notify_connection_drop(
+    http_config: &HttpConnectionConfig,
+    category: &str,
+    port: u16,
+    connection_id: &str,
+) {
+    let url = format!(
+        "{}/mcp/connection/{}",
+        category_base_url(http_config, port),
+        connection_id
+    );
+
+    log::debug!(
+        "Notifying {} server of connection {} drop",
+        category,
+        connection_id
+    );
+
+    // Send DELETE request with 5-second timeout
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(5))
+        .build()
+        .unwrap();
+
+    match client.delete(&url).send().await {
+        Ok(response) => {
+            if response.status().is_success() || response.status() == 204 {
+                log::debug!(
+                    "{} server acknowledged connection {} cleanup",
+                    category,
+                    connection_id
+                );
+            } else {
+                log::warn!(
+                    "{} server returned {} for connection {} cleanup",
+                    category,
+                    response.status(),
+                    connection_id
+                );
+            }
+        }
+        Err(e) => {
+            log::warn!(
+                "Failed to notify {} server of connection {} drop: {}",
+                category,
+                connection_id,
+                e
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(host: &str, no_tls: bool) -> HttpConnectionConfig {
+        HttpConnectionConfig {
+            host: host.to_string(),
+            no_tls,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_category_base_url_brackets_ipv6() {
+        assert_eq!(category_base_url(&config("127.0.0.1", true), 30437), "http://127.0.0.1:30437");
+        assert_eq!(category_base_url(&config("::1", true), 30437), "http://[::1]:30437");
+        assert_eq!(category_base_url(&config("[::1]", true), 30437), "http://[::1]:30437");
+        assert_eq!(category_base_url(&config("mcp.kodegen.ai", false), 30437), "https://mcp.kodegen.ai:30437");
+    }
+
+    #[test]
+    fn test_validate_host_rejects_zone_ids() {
+        assert_eq!(validate_host("::1").unwrap(), "::1");
+        assert_eq!(validate_host("fe80::1").unwrap(), "fe80::1");
+        assert_eq!(validate_host("mcp.kodegen.ai").unwrap(), "mcp.kodegen.ai");
+
+        for (host, zone) in [
+            ("fe80::1%eth0", "eth0"),
+            ("[fe80::1%25eth0]", "eth0"),
+            ("fe80::1%3", "3"),
+            ("fe80::1%253", "253"),
+            ("fe80::1%2525", "2525"),
+            ("[fe80::1%2525]", "25"),
+        ] {
+            let err = validate_host(host).unwrap_err();
+            assert!(err.contains("zone IDs are not supported"), "{host}: {err}");
+            assert!(err.contains(&format!("zone '{zone}' in")), "{host}: {err}");
+        }
+    }
+
+    #[test]
+    fn test_category_status_map_counts() {
+        let serving = vec!["git".to_string(), "filesystem".to_string()];
+        let offline = vec!["browser".to_string()];
+
+        // filesystem is connected but every tool in it is filtered out
+        let categories = category_status_map(&serving, &offline, "online", |category| {
+            if category == "git" { 3 } else { 0 }
+        });
+
+        assert_eq!(categories["git"], json!({ "status": "online", "tools": 3 }));
+        assert_eq!(categories["filesystem"], json!({ "status": "online", "tools": 0 }));
+        assert_eq!(categories["browser"], json!({ "status": "offline", "tools": 0 }));
+        assert_eq!(categories.len(), 3);
+
+        let replayed = category_status_map(&serving, &[], "replay", |_| 1);
+        assert_eq!(replayed["git"], json!({ "status": "replay", "tools": 1 }));
+    }
+}
@@ -23,14 +23,31 @@
 //!
 //! This ensures that even if two clients use the same session_id, they map
 //! to different UUIDs on the HTTP server side.
+//!
+//! Sequential thinking calls that opt in with `shared: true` are not mapped
+//! per connection, so several agents can contribute to the same named reasoning
+//! session. Shared names are scoped to the workspace (a hash of the git root):
+//! `shared-{scope}-{name}` is sent to the server, so clients in other
+//! repositories on the same category server never join each other's sessions.
+//! Every other tool is always mapped.
 
 use dashmap::DashMap;
+use serde_json::{Map, Value};
 use std::sync::Arc;
 use uuid::Uuid;
 
 /// Key for session mapping: (connection_id, client_session_id)
 type SessionKey = (String, String);
 
+/// Tools whose sessions may be shared across connections with `shared: true`
+const SHARED_SESSION_TOOLS: &[&str] = &["sequential_thinking"];
+
+/// Whether a tool call addresses a shared session that is scoped to the workspace, not the connection
+fn is_shared_session(tool_name: &str, args: &Map<String, Value>) -> bool {
+    SHARED_SESSION_TOOLS.contains(&tool_name)
+        && args.get("shared").and_then(Value::as_bool).unwrap_or(false)
+}
+
 /// Session ID mapper for isolating stdio connections
 #[derive(Clone)]
 pub struct SessionMapper {
@@ -87,6 +104,46 @@ impl SessionMapper {
         server_session_id
     }
 
+    /// Replace the session_id in a tool call's arguments with the server session ID
+    ///
+    /// Arguments without a string `session_id` are left untouched. Shared
+    /// sessions of tools listed in `SHARED_SESSION_TOOLS` get the same ID on
+    /// every connection with the same `shared_scope`; all others are mapped
+    /// to a per-connection UUID.
+    ///
+    /// # Arguments
+    ///
+    /// * `connection_id` - Unique identifier for the stdio connection
+    /// * `shared_scope` - Namespace for shared session names (workspace hash)
+    /// * `tool_name` - Tool being called
+    /// * `args` - Tool arguments, updated in place
+    pub fn map_arguments(
+        &self,
+        connection_id: &str,
+        shared_scope: &str,
+        tool_name: &str,
+        args: &mut Map<String, Value>,
+    ) {
+        let Some(client_session_id) = args.get("session_id").and_then(Value::as_str) else {
+            return;
+        };
+
+        let server_session_id = if is_shared_session(tool_name, args) {
+            format!("shared-{}-{}", shared_scope, client_session_id)
+        } else {
+            self.map_session_id(connection_id, client_session_id)
+        };
+
+        log::debug!(
+            "Mapped session_id for tool '{}': client='{}' -> server='{}'",
+            tool_name,
+            client_session_id,
+            server_session_id
+        );
+
+        args.insert("session_id".to_string(), Value::String(server_session_id));
+    }
+
     /// Get the mapped server session ID for a given client session ID
     ///
     /// # Arguments
@@ -251,4 +308,37 @@ mod tests {
         mapper.cleanup_connection("conn-1");
         assert!(mapper.is_empty());
     }
+
+    #[test]
+    fn test_map_arguments_shared_only_for_sequential_thinking() {
+        let mapper = SessionMapper::new();
+        let shared = serde_json::json!({ "session_id": "plan", "shared": true });
+
+        // Shared sequential thinking sessions get the same ID on every connection in a workspace
+        let mut first = shared.as_object().unwrap().clone();
+        let mut second = shared.as_object().unwrap().clone();
+        mapper.map_arguments("conn-1", "repo-a", "sequential_thinking", &mut first);
+        mapper.map_arguments("conn-2", "repo-a", "sequential_thinking", &mut second);
+        assert_eq!(first["session_id"], "shared-repo-a-plan");
+        assert_eq!(first["session_id"], second["session_id"]);
+
+        // ... but not across workspaces
+        let mut other_repo = shared.as_object().unwrap().clone();
+        mapper.map_arguments("conn-3", "repo-b", "sequential_thinking", &mut other_repo);
+        assert_ne!(other_repo["session_id"], first["session_id"]);
+
+        // Other tools are mapped even when they ask to share
+        let mut terminal = shared.as_object().unwrap().clone();
+        mapper.map_arguments("conn-1", "repo-a", "terminal_start_command", &mut terminal);
+        assert_eq!(
+            terminal["session_id"],
+            mapper.get_mapped_id("conn-1", "plan").unwrap().as_str()
+        );
+
+        // Unshared sequential thinking sessions are mapped too
+        let mut private = serde_json::json!({ "session_id": "notes" }).as_object().unwrap().clone();
+        mapper.map_arguments("conn-1", "repo-a", "sequential_thinking", &mut private);
+        assert_ne!(private["session_id"], "notes");
+        assert_eq!(mapper.len(), 2);
+    }
 }
@@ -0,0 +1,160 @@
+//! Tool groups mode: one gateway tool per category
+//!
+//! Some clients (e.g. Claude Desktop) cap how many tools a server may expose.
+//! With `--tool-groups`, list_tools returns a single gateway tool per connected
+//! category (`filesystem_tools`, `git_tools`, ...) instead of every tool. Each
+//! gateway accepts a sub-tool name plus its arguments and the proxy dispatches
+//! the call internally, so the full toolset stays reachable under a small budget.
+//!
+//! Gateway arguments:
+//! - `{}` lists the category's sub-tools with their descriptions
+//! - `{"tool": "fs_read_file", "describe": true}` returns the sub-tool's input schema
+//! - `{"tool": "fs_read_file", "args": {...}}` calls the sub-tool
+
+use rmcp::ErrorData as McpError;
+use rmcp::model::{JsonObject, Tool};
+use serde_json::json;
+use std::sync::Arc;
+
+/// Suffix appended to a category name to form its gateway tool name
+const GATEWAY_SUFFIX: &str = "_tools";
+
+/// A parsed gateway tool call
+#[derive(Debug, Clone, PartialEq)]
+pub enum GatewayRequest {
+    /// List the sub-tools in the category
+    List,
+    /// Describe one sub-tool (description and input schema)
+    Describe(String),
+    /// Call a sub-tool with the given arguments
+    Call { tool: String, args: JsonObject },
+}
+
+impl GatewayRequest {
+    /// Parse gateway arguments into a request
+    pub fn parse(arguments: Option<JsonObject>) -> Result<Self, McpError> {
+        let mut arguments = arguments.unwrap_or_default();
+
+        let tool = match arguments.remove("tool") {
+            None | Some(serde_json::Value::Null) => return Ok(Self::List),
+            Some(serde_json::Value::String(tool)) => tool,
+            Some(_) => {
+                return Err(McpError::invalid_params("'tool' must be a string", None));
+            }
+        };
+
+        if arguments.get("describe").and_then(|v| v.as_bool()).unwrap_or(false) {
+            return Ok(Self::Describe(tool));
+        }
+
+        let args = match arguments.remove("args") {
+            None | Some(serde_json::Value::Null) => JsonObject::new(),
+            Some(serde_json::Value::Object(args)) => args,
+            Some(_) => {
+                return Err(McpError::invalid_params("'args' must be a JSON object", None));
+            }
+        };
+
+        Ok(Self::Call { tool, args })
+    }
+}
+
+/// Gateway tool name for a category (e.g. "git" -> "git_tools")
+pub fn gateway_name(category: &str) -> String {
+    format!("{category}{GATEWAY_SUFFIX}")
+}
+
+/// Category addressed by a gateway tool name, if `name` is one
+pub fn gateway_category(name: &str) -> Option<&str> {
+    name.strip_suffix(GATEWAY_SUFFIX).filter(|category| !category.is_empty())
+}
+
+/// Build the gateway tool for a category from its sub-tool names
+pub fn gateway_tool(category: &str, sub_tools: &[&str]) -> Tool {
+    let description = format!(
+        "Gateway to {count} {category} tool{plural}: {names}. \
+         Call with {{\"tool\": <name>, \"args\": {{...}}}}. \
+         Omit \"tool\" to list the tools with descriptions, or pass \"describe\": true \
+         to get a tool's input schema before calling it.",
+        count = sub_tools.len(),
+        plural = if sub_tools.len() == 1 { "" } else { "s" },
+        names = sub_tools.join(", "),
+    );
+
+    let schema = json!({
+        "type": "object",
+        "properties": {
+            "tool": {
+                "type": "string",
+                "enum": sub_tools,
+                "description": "Sub-tool to call. Omit to list available sub-tools."
+            },
+            "args": {
+                "type": "object",
+                "description": "Arguments for the sub-tool (see describe for its schema)"
+            },
+            "describe": {
+                "type": "boolean",
+                "description": "Return the sub-tool's description and input schema instead of calling it"
+            }
+        }
+    });
+
+    let input_schema = match schema {
+        serde_json::Value::Object(obj) => Arc::new(obj),
+        _ => Arc::new(JsonObject::new()),
+    };
+
+    Tool {
+        name: gateway_name(category).into(),
+        title: None,
+        description: Some(description.into()),
+        input_schema,
+        output_schema: None,
+        annotations: None,
+        icons: None,
+        meta: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gateway_name_round_trip() {
+        assert_eq!(gateway_name("git"), "git_tools");
+        assert_eq!(gateway_category("git_tools"), Some("git"));
+        assert_eq!(gateway_category("fs_read_file"), None);
+        assert_eq!(gateway_category("_tools"), None);
+    }
+
+    #[test]
+    fn test_parse_list_and_describe() {
+        assert_eq!(GatewayRequest::parse(None).unwrap(), GatewayRequest::List);
+        assert_eq!(
+            GatewayRequest::parse(json!({ "tool": "git_log", "describe": true }).as_object().cloned()).unwrap(),
+            GatewayRequest::Describe("git_log".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_call() {
+        let request =
+            GatewayRequest::parse(json!({ "tool": "git_log", "args": { "limit": 5 } }).as_object().cloned()).unwrap();
+
+        assert_eq!(
+            request,
+            GatewayRequest::Call {
+                tool: "git_log".to_string(),
+                args: json!({ "limit": 5 }).as_object().unwrap().clone(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_non_object_args() {
+        assert!(GatewayRequest::parse(json!({ "tool": "git_log", "args": "--all" }).as_object().cloned()).is_err());
+        assert!(GatewayRequest::parse(json!({ "tool": 42 }).as_object().cloned()).is_err());
+    }
+}
@@ -0,0 +1,354 @@
+//! Per-workspace tool usage statistics for list_tools ordering
+//!
+//! Clients with tool-count limits often keep only the first N tools they are
+//! offered. When `--order-tools-by-usage` is enabled, the proxy records how
+//! often each tool is called (and how often it succeeds) in the current
+//! workspace, and list_tools returns the most useful tools first.
+//!
+//! Statistics are persisted per workspace (git root, or the working directory
+//! outside a repository) so ordering survives restarts:
+//! `{data_local_dir}/kodegen/usage/{workspace-hash}.json`
+//!
+//! Saves merge this process's new calls into whatever is on disk, under a lock
+//! file, so several kodegen processes in one workspace add to the same counts.
+
+use dashmap::DashMap;
+use rmcp::model::Tool;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Usage older than this many seconds counts half as much (one week)
+const HALF_LIFE_SECS: f64 = 7.0 * 24.0 * 60.0 * 60.0;
+
+/// Usage counters for a single tool
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ToolUsage {
+    /// Total number of calls
+    pub calls: u64,
+    /// Calls that returned a non-error result
+    pub successes: u64,
+    /// Unix timestamp (seconds) of the most recent call
+    pub last_used: i64,
+}
+
+impl ToolUsage {
+    /// Ranking score: call frequency weighted by success rate, decayed by time since last use
+    ///
+    /// Frequency grows logarithmically and the success rate counts squared, so
+    /// a tool that always works outranks a busier one that fails half the time.
+    fn score(&self, now: i64) -> f64 {
+        if self.calls == 0 {
+            return 0.0;
+        }
+
+        let success_rate = self.successes as f64 / self.calls as f64;
+        let frequency = (self.calls as f64).ln_1p();
+        let age = (now - self.last_used).max(0) as f64;
+        frequency * success_rate * success_rate * 0.5_f64.powf(age / HALF_LIFE_SECS)
+    }
+
+    /// Add another set of counters to these
+    fn merge(&mut self, other: &ToolUsage) {
+        self.calls += other.calls;
+        self.successes += other.successes;
+        self.last_used = self.last_used.max(other.last_used);
+    }
+}
+
+/// Tool usage statistics for one workspace
+#[derive(Clone)]
+pub struct UsageStats {
+    /// Persistence file (None keeps statistics in memory only)
+    path: Option<PathBuf>,
+    /// Usage counters keyed by tool name (saved counts plus pending calls)
+    tools: Arc<DashMap<String, ToolUsage>>,
+    /// Calls recorded since the last save, merged into the file by the next flush
+    pending: Arc<std::sync::Mutex<HashMap<String, ToolUsage>>>,
+    /// Serializes flushes within this process
+    writer: Arc<tokio::sync::Mutex<()>>,
+}
+
+impl UsageStats {
+    /// Create empty in-memory statistics (nothing is persisted)
+    pub fn in_memory() -> Self {
+        Self::new(None, HashMap::new())
+    }
+
+    fn new(path: Option<PathBuf>, tools: HashMap<String, ToolUsage>) -> Self {
+        Self {
+            path,
+            tools: Arc::new(tools.into_iter().collect()),
+            pending: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            writer: Arc::new(tokio::sync::Mutex::new(())),
+        }
+    }
+
+    /// Load statistics for a workspace, starting empty if none were saved yet
+    pub fn load(workspace: &Path) -> Self {
+        let Some(path) = usage_file(workspace) else {
+            log::warn!("No local data directory available, tool usage will not be persisted");
+            return Self::in_memory();
+        };
+
+        Self::load_file(path)
+    }
+
+    fn load_file(path: PathBuf) -> Self {
+        let tools = read_stats(&path);
+
+        log::debug!(
+            "Loaded usage statistics for {} tool(s) from {}",
+            tools.len(),
+            path.display()
+        );
+
+        Self::new(Some(path), tools)
+    }
+
+    /// Record a tool call and persist it in the background
+    pub fn record(&self, tool: &str, success: bool) {
+        let call = ToolUsage {
+            calls: 1,
+            successes: u64::from(success),
+            last_used: chrono::Utc::now().timestamp(),
+        };
+
+        {
+            // Update both maps under the pending lock so a concurrent flush sees them agree
+            let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+            pending.entry(tool.to_string()).or_default().merge(&call);
+            self.tools.entry(tool.to_string()).or_default().merge(&call);
+        }
+
+        if self.path.is_some() {
+            let stats = self.clone();
+            tokio::spawn(async move { stats.flush().await });
+        }
+    }
+
+    /// Merge pending calls into the statistics file
+    ///
+    /// Flushes run one at a time; a flush that finds nothing pending (because
+    /// an earlier one already saved its calls) returns immediately.
+    async fn flush(&self) {
+        let Some(path) = self.path.clone() else {
+            return;
+        };
+
+        let _writer = self.writer.lock().await;
+
+        let calls = std::mem::take(&mut *self.pending.lock().unwrap_or_else(|e| e.into_inner()));
+        if calls.is_empty() {
+            return;
+        }
+
+        let saved = tokio::task::spawn_blocking(move || {
+            let result = merge_and_save(&path, &calls);
+            (path, calls, result)
+        })
+        .await;
+
+        match saved {
+            Ok((_path, _calls, Ok(merged))) => self.refresh(merged),
+            Ok((path, calls, Err(e))) => {
+                log::warn!("Failed to save tool usage to {}: {}", path.display(), e);
+                // Keep the calls for the next flush
+                let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+                for (name, usage) in calls {
+                    pending.entry(name).or_default().merge(&usage);
+                }
+            }
+            Err(e) => log::warn!("Tool usage save task failed: {}", e),
+        }
+    }
+
+    /// Replace in-memory counts with the saved ones (which include other processes' calls)
+    fn refresh(&self, saved: HashMap<String, ToolUsage>) {
+        let pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+
+        for (name, mut usage) in saved {
+            if let Some(unsaved) = pending.get(&name) {
+                usage.merge(unsaved);
+            }
+            self.tools.insert(name, usage);
+        }
+    }
+
+    /// Sort tools by usage score (highest first), keeping name order for ties
+    pub fn sort_tools(&self, tools: &mut [Tool]) {
+        let now = chrono::Utc::now().timestamp();
+        let score = |tool: &Tool| {
+            self.tools
+                .get(&*tool.name)
+                .map(|usage| usage.score(now))
+                .unwrap_or(0.0)
+        };
+
+        tools.sort_by(|a, b| {
+            score(b)
+                .total_cmp(&score(a))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+    }
+}
+
+/// Read saved statistics (empty when missing or unreadable)
+fn read_stats(path: &Path) -> HashMap<String, ToolUsage> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Add calls to the saved statistics and write them back atomically (temp file + rename)
+///
+/// Holds an exclusive lock on `{file}.lock` for the read-merge-write so other
+/// kodegen processes in the same workspace cannot overwrite each other's counts.
+/// Returns the merged statistics.
+fn merge_and_save(
+    path: &Path,
+    calls: &HashMap<String, ToolUsage>,
+) -> anyhow::Result<HashMap<String, ToolUsage>> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let lock = std::fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(path.with_extension("json.lock"))?;
+    lock.lock()?;
+
+    let mut merged = read_stats(path);
+    for (name, usage) in calls {
+        merged.entry(name.clone()).or_default().merge(usage);
+    }
+
+    let tmp = path.with_extension(format!("json.{}.tmp", uuid::Uuid::new_v4()));
+    std::fs::write(&tmp, serde_json::to_vec(&merged)?)?;
+    std::fs::rename(&tmp, path)?;
+    Ok(merged)
+}
+
+/// Stable hash of a workspace path (FNV-1a, unlike std's DefaultHasher, is stable across builds)
+pub(crate) fn workspace_hash(workspace: &Path) -> u64 {
+    workspace
+        .to_string_lossy()
+        .bytes()
+        .fold(0xcbf29ce484222325_u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+        })
+}
+
+/// Resolve the persistence file for a workspace
+fn usage_file(workspace: &Path) -> Option<PathBuf> {
+    let hash = workspace_hash(workspace);
+
+    Some(
+        dirs::data_local_dir()?
+            .join("kodegen")
+            .join("usage")
+            .join(format!("{hash:016x}.json")),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool(name: &'static str) -> Tool {
+        Tool {
+            name: name.into(),
+            title: None,
+            description: None,
+            input_schema: Arc::new(serde_json::Map::new()),
+            output_schema: None,
+            annotations: None,
+            icons: None,
+            meta: None,
+        }
+    }
+
+    fn names(tools: &[Tool]) -> Vec<&str> {
+        tools.iter().map(|t| &*t.name).collect()
+    }
+
+    #[tokio::test]
+    async fn test_sort_by_successful_calls() {
+        let stats = UsageStats::in_memory();
+        stats.record("fs_read_file", true);
+        stats.record("fs_read_file", true);
+        stats.record("terminal", true);
+        stats.record("git_log", false);
+        stats.record("git_log", false);
+        stats.record("git_log", false);
+
+        let mut tools = vec![tool("git_log"), tool("terminal"), tool("fs_read_file"), tool("fs_search")];
+        stats.sort_tools(&mut tools);
+
+        // Failed calls don't earn rank; unused and failing tools fall back to name order
+        assert_eq!(names(&tools), vec!["fs_read_file", "terminal", "fs_search", "git_log"]);
+    }
+
+    #[test]
+    fn test_score_decays_with_age() {
+        let usage = ToolUsage {
+            calls: 4,
+            successes: 4,
+            last_used: 0,
+        };
+
+        assert_eq!(usage.score(0), 5.0_f64.ln());
+        assert!((usage.score(HALF_LIFE_SECS as i64) - 5.0_f64.ln() / 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_score_weighs_success_rate() {
+        let usage = |calls, successes| ToolUsage { calls, successes, last_used: 0 };
+
+        // Always works beats busier but failing half the time
+        assert!(usage(10, 10).score(0) > usage(100, 50).score(0));
+        // At the same success rate, more calls rank higher
+        assert!(usage(100, 90).score(0) > usage(10, 9).score(0));
+        assert_eq!(usage(3, 0).score(0), 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_flush_merges_with_other_processes() {
+        let dir = std::env::temp_dir().join(format!("kodegen-usage-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("usage.json");
+
+        // Two processes that loaded the (empty) file before either saved
+        let first = UsageStats::load_file(path.clone());
+        let second = UsageStats::load_file(path.clone());
+
+        first.record("fs_read_file", true);
+        first.record("fs_read_file", false);
+        first.flush().await;
+        second.record("fs_read_file", true);
+        second.record("git_log", true);
+        second.flush().await;
+
+        let saved = read_stats(&path);
+        assert_eq!(saved["fs_read_file"].calls, 3);
+        assert_eq!(saved["fs_read_file"].successes, 2);
+        assert_eq!(saved["git_log"].calls, 1);
+
+        // The later flush also picked up the first process's calls
+        assert_eq!(second.tools.get("fs_read_file").unwrap().calls, 3);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_usage_file_is_stable_per_workspace() {
+        let a = usage_file(Path::new("/work/project-a"));
+        let b = usage_file(Path::new("/work/project-b"));
+
+        assert_eq!(a, usage_file(Path::new("/work/project-a")));
+        assert_ne!(a, b);
+    }
+}